@@ -1,24 +1,116 @@
+use std::{fs, time::Duration};
+
 use anyhow::anyhow;
-use chrono::{Datelike, Weekday};
+use chrono::{Datelike, Local, Weekday};
 use iced::{
-  widget::{column, container, row, scrollable, Rule},
-  Length, Sandbox,
+  clipboard, executor, time,
+  time::Instant,
+  widget::{self, column, container, row, scrollable, Rule},
+  Application, Command, Length, Subscription, Theme,
+};
+use iced_aw::Modal;
+use maiq_shared::{
+  default::{DefaultDay, DefaultLesson},
+  utils::time::{now_date, now_date_offset},
 };
-use maiq_shared::utils::time::{now_date, now_date_offset};
 
 use crate::{
-  env::DEFAULTS,
-  view::{editor::SnapshotEditor, notification::Notification, toolbar::toolbar, Component, EditorMessage},
+  changes,
+  compose::Sources,
+  curriculum,
+  dictionary::Dictionary,
+  diff::{self, Change},
+  env,
+  i18n::{tr, trf, Key},
+  import::{self, Imported},
+  occupancy,
+  ordering::SortOrder,
+  pivot,
+  recovery::{self, Recovery},
+  references::{self, References},
+  rotation::Rotations,
+  search::{self, Scope},
+  settings,
+  shortcuts::{self, Shortcut},
+  slots, stats, substitution,
+  view::{
+    changes::ChangesEditor,
+    cheat_sheet::cheat_sheet,
+    curriculum::CurriculumView,
+    default_group::{GroupComponent, ViewOptions},
+    editor::{self, SnapshotEditor},
+    final_day::FinalDayView,
+    gaps::GapsView,
+    history::HistoryView,
+    notification::{Level, Notification},
+    palette,
+    recovery::recovery_prompt,
+    references::ReferencesEditor,
+    rooms::RoomsView,
+    search::SearchBar,
+    settings::SettingsEditor,
+    slots::{SlotsView, Target},
+    stats::StatsView,
+    substitution::{Substitution, SubstitutionView},
+    teachers::TeachersView,
+    toolbar::{map_weekday_to_str, toolbar},
+    ChangesMessage, Component, CurriculumMessage, EditorMessage, FinalDayMessage, GapsMessage, GroupMessage, HistoryMessage,
+    LessonMessage, ReferencesMessage, RoomsMessage, SearchMessage, SettingsMessage, SlotsMessage, StatsMessage,
+    SubstitutionMessage, TeachersMessage,
+  },
 };
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Screen {
+  #[default]
+  Editor,
+  References,
+  Changes,
+  Final,
+  Teachers,
+  Rooms,
+  Slots,
+  Substitution,
+  Stats,
+  Gaps,
+  Curriculum,
+  History,
+  Settings,
+}
+
 #[derive(Debug, Clone)]
 pub enum AppMessage {
   Editor(EditorMessage),
-  Sort,
+  References(ReferencesMessage),
+  Search(SearchMessage),
+  Settings(SettingsMessage),
+  Changes(ChangesMessage),
+  Final(FinalDayMessage),
+  Teachers(TeachersMessage),
+  Rooms(RoomsMessage),
+  Slots(SlotsMessage),
+  FindSlots,
+  Substitution(SubstitutionMessage),
+  FindSubstitutes,
+  Stats(StatsMessage),
+  Gaps(GapsMessage),
+  Curriculum(CurriculumMessage),
+  History(HistoryMessage),
+  Open(Screen),
+  Shortcut(Shortcut),
+  Sort(SortOrder),
+  Copy,
+  CopyToDay(Weekday),
   Import(usize),
   ImportToday,
   ImportNext,
+  ImportHtml,
+  HtmlImported(Result<Option<(String, Imported)>, String>),
   Export,
+  Completed(Result<Option<String>, String>),
+  Tick(Instant),
+  Autosave,
+  Recover(bool),
   New,
   DeleteNotification(usize),
   Dummy,
@@ -27,15 +119,35 @@ pub enum AppMessage {
 
 #[derive(Default)]
 pub struct App {
+  screen: Screen,
   editor: SnapshotEditor,
+  references: ReferencesEditor,
+  search: SearchBar,
+  settings: SettingsEditor,
+  history: HistoryView,
+  changes: ChangesEditor,
+  final_day: FinalDayView,
+  teachers: TeachersView,
+  rooms: RoomsView,
+  slots: SlotsView,
+  substitution: SubstitutionView,
+  stats: StatsView,
+  gaps: GapsView,
+  curriculum: CurriculumView,
+  show_cheat_sheet: bool,
   notifications: Vec<Notification>,
+  defaults: Vec<DefaultDay>,
+  dictionary: Dictionary,
+  theme: Theme,
+  recovery: Option<(Recovery, Vec<Change>)>,
+  autosaved: String,
 }
 
-fn resolve_weekday(today: bool) -> Weekday {
-  let date = match today {
-    true => now_date(),
-    false => now_date_offset(1),
-  };
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+const IMPORT_WARNINGS_LIMIT: usize = 5;
+
+// Nothing is scheduled on Sunday, so it stands for the coming Monday
+fn resolve_weekday(date: impl Datelike) -> Weekday {
   let weekday = date.weekday();
   if weekday == Weekday::Sun {
     weekday.succ()
@@ -44,73 +156,591 @@ fn resolve_weekday(today: bool) -> Weekday {
   }
 }
 
-impl Sandbox for App {
-  type Message = AppMessage;
+fn find_default(defaults: &[DefaultDay], weekday: Weekday) -> anyhow::Result<&DefaultDay> {
+  defaults
+    .iter()
+    .find(|d| d.day == weekday)
+    .ok_or_else(|| anyhow!(trf(Key::NoDefaultFor, &[&map_weekday_to_str(weekday)])))
+}
 
-  fn new() -> Self {
-    App::default()
+fn days_list(days: &[Weekday]) -> String {
+  match days.is_empty() {
+    true => "—".into(),
+    false => days
+      .iter()
+      .map(|d| map_weekday_to_str(*d))
+      .collect::<Vec<_>>()
+      .join(", "),
   }
+}
 
-  fn title(&self) -> String {
-    "Iced!".into()
-  }
+impl App {
+  fn rename_reference(&mut self, idx: usize) -> anyhow::Result<Option<String>> {
+    let Some((kind, from, to)) = self.references.rename_target(idx) else {
+      return Ok(None);
+    };
 
-  fn update(&mut self, message: Self::Message) {
-    println!("Message: {:?}", message);
-    let res = match message {
-      AppMessage::Editor(m) => {
-        self.editor.update(m);
-        Ok(None)
+    // Every saved default is renamed, the loaded one too, as it's also in the editor; the editor's lessons
+    // are counted in its place
+    let loaded = self.editor.loaded_day();
+    let mut in_loaded = 0;
+    let renamed = self.editor.rename(kind, &from, &to);
+    let (count, written) = self.update_defaults(|day| {
+      let count = references::rename(kind, &from, &to, day);
+      if Some(day.day) == loaded {
+        in_loaded = count;
       }
-      AppMessage::Import(idx) => self.editor.set_groups(&DEFAULTS[idx]),
-      AppMessage::ImportToday => self
-        .editor
-        .set_groups(&DEFAULTS[resolve_weekday(true).number_from_monday() as usize - 1]),
-      AppMessage::ImportNext => self
-        .editor
-        .set_groups(&DEFAULTS[resolve_weekday(false).number_from_monday() as usize - 1]),
-      AppMessage::Sort => self.editor.sort(),
-      AppMessage::Export => self.editor.save_to_file(),
-      AppMessage::DeleteNotification(idx) => {
-        self.notifications.remove(idx);
-        Ok(None)
-      },
-      AppMessage::Nothing => Ok(None),
-      _ => Err(anyhow!("Not yet implemented!")),
+      count
+    })?;
+
+    // patterns follow a renamed group on every day, so they are saved along with the defaults
+    if kind == references::Kind::Group {
+      self.editor.rotations().save()?;
+    }
+    self.references.apply_rename(idx);
+    self.references.references().save()?;
+    self.references.refresh(&self.defaults);
+    Ok(Some(trf(Key::Renamed, &[&from, &to, &(renamed + count - in_loaded), &days_list(&written)])))
+  }
+
+  fn replace(&mut self) -> anyhow::Result<Option<String>> {
+    let Some((find, replacement, scope)) = self
+      .search
+      .replacement()
+      .map(|(f, r, s)| (f.to_string(), r.to_string(), s))
+    else {
+      return Ok(None);
     };
 
-    if let Err(err) = &res {
+    // As with renaming, every saved default is changed and the loaded one is counted in the editor.
+    // Renamed groups keep their custom patterns.
+    let mut replaced = self.editor.replace(&find, &replacement);
+    let mut written = vec![];
+    if scope == Scope::Week {
+      let loaded = self.editor.loaded_day();
+      let (mut in_loaded, mut renamed_groups) = (0, vec![]);
+      let (count, days) = self.update_defaults(|day| {
+        renamed_groups.extend(
+          search::renamed_groups(day, &find, &replacement)
+            .into_iter()
+            .map(|(from, to)| (day.day, from, to)),
+        );
+        let count = search::replace(day, &find, &replacement);
+        if Some(day.day) == loaded {
+          in_loaded = count;
+        }
+        count
+      })?;
+      replaced += count - in_loaded;
+      written = days;
+
+      if !renamed_groups.is_empty() {
+        let rotations = self.editor.rotations_mut();
+        for (day, from, to) in renamed_groups {
+          rotations.rename_group(day, &from, &to);
+        }
+        rotations.save()?;
+      }
+    }
+
+    self.search.update(SearchMessage::Replace);
+    Ok(Some(trf(Key::Replaced, &[&replaced, &days_list(&written)])))
+  }
+
+  fn copy(&mut self) -> Command<AppMessage> {
+    let copied = self.editor.copy_selection();
+    if copied == 0 {
       self
         .notifications
-        .push(Notification::error("Ошибка!", "Не реализовано"));
-      eprintln!("{}", err);
+        .push(Notification::error(tr(Key::Error), tr(Key::NothingSelected)));
+      return Command::none();
     }
 
-    if let Ok(Some(ok)) = &res {
-      self.notifications.push(Notification::ok("Инфо", ok));
+    self
+      .notifications
+      .push(Notification::ok(tr(Key::Info), trf(Key::Copied, &[&copied])));
+    match serde_json::to_string_pretty(self.editor.clipboard()) {
+      Ok(json) => clipboard::write(json),
+      Err(_) => Command::none(),
     }
   }
 
-  fn view(&self) -> iced::Element<'_, Self::Message> {
-    let groups = scrollable(
-      column(
-        self
-          .editor
-          .groups()
-          .enumerate()
-          .map(|(idx, group)| {
-            group
-              .view()
-              .map(move |msg| AppMessage::Editor(EditorMessage::Group((idx, msg))))
-          })
-          .collect(),
-      )
-      .padding([0, 15]),
+  fn copy_to_day(&mut self, weekday: Weekday) -> anyhow::Result<Option<String>> {
+    let selected = self
+      .editor
+      .selected()
+      .into_iter()
+      .map(|(group, lesson)| (group.name.clone(), lesson.clone()))
+      .collect::<Vec<_>>();
+    if selected.is_empty() {
+      return Err(anyhow!(tr(Key::NothingSelected)));
+    }
+
+    // The editor's day is copied into the editor, so the next export doesn't overwrite the copy
+    let copied = match self.editor.snapshot().day == weekday {
+      true => self.editor.add_lessons(&selected),
+      false => self.add_to_default(weekday, &selected)?,
+    };
+    Ok(Some(trf(Key::CopiedTo, &[&copied, &map_weekday_to_str(weekday)])))
+  }
+
+  fn add_to_default(&mut self, weekday: Weekday, lessons: &[(String, DefaultLesson)]) -> anyhow::Result<usize> {
+    if !self.defaults.iter().any(|d| d.day == weekday) {
+      self.defaults.push(DefaultDay { day: weekday, groups: vec![] });
+      self.defaults.sort_by_key(|d| d.day.num_days_from_monday());
+    }
+
+    self
+      .update_defaults(|day| match day.day == weekday {
+        true => editor::add_lessons(day, lessons),
+        false => 0,
+      })
+      .map(|(count, _)| count)
+  }
+
+  // The lesson leaves the saved default of the editor's day and joins the target day's one. Both files are
+  // written together: if the second write fails, the first one is rolled back. A lesson that isn't saved yet
+  // has nothing to leave.
+  fn move_between_defaults(
+    &mut self,
+    to: Weekday,
+    group: &str,
+    original: &DefaultLesson,
+    lesson: DefaultLesson,
+  ) -> anyhow::Result<()> {
+    let from = self.editor.snapshot().day;
+    let previous = self.defaults.iter().find(|d| d.day == to).cloned();
+    let mut target = previous.clone().unwrap_or(DefaultDay { day: to, groups: vec![] });
+    editor::add_lessons(&mut target, &[(group.to_string(), lesson)]);
+
+    let source = self
+      .defaults
+      .iter()
+      .find(|d| d.day == from)
+      .cloned()
+      .and_then(|mut day| {
+        let g = day.groups.iter_mut().find(|g| g.name == group)?;
+        let idx = g
+          .lessons
+          .iter()
+          .position(|l| diff::describe(l) == diff::describe(original))?;
+        g.lessons.remove(idx);
+        Some(day)
+      });
+
+    env::write_default(&target)?;
+    if let Some(source) = &source {
+      if let Err(err) = env::write_default(source) {
+        _ = match &previous {
+          Some(previous) => env::write_default(previous).map(|_| ()),
+          None => fs::remove_file(env::default_path(to)).map_err(Into::into),
+        };
+        return Err(err);
+      }
+    }
+
+    self
+      .defaults
+      .retain(|d| d.day != to && source.as_ref().is_none_or(|s| s.day != d.day));
+    self
+      .defaults
+      .extend(source.into_iter().chain(std::iter::once(target)));
+    self.defaults.sort_by_key(|d| d.day.num_days_from_monday());
+    self.dictionary = Dictionary::from_days(&self.defaults);
+    self.references.refresh(&self.defaults);
+    Ok(())
+  }
+
+  // The week as it's being edited: a day loaded into the editor stands in for its saved default
+  fn week(&self) -> Vec<DefaultDay> {
+    let Some(loaded) = self.editor.loaded_day() else {
+      return self.defaults.clone();
+    };
+    let mut days = self
+      .defaults
+      .iter()
+      .filter(|d| d.day != loaded)
+      .cloned()
+      .chain(std::iter::once(self.editor.snapshot().clone()))
+      .collect::<Vec<_>>();
+    days.sort_by_key(|d| d.day.num_days_from_monday());
+    days
+  }
+
+  fn find_slots(&mut self) -> anyhow::Result<Option<String>> {
+    let Some((group, Some(lesson))) = self.editor.cursor() else {
+      return Err(anyhow!(tr(Key::NoSlotTarget)));
+    };
+    let Some((g, l)) = self.editor.lesson(group, lesson) else {
+      return Err(anyhow!(tr(Key::NoSlotTarget)));
+    };
+    let target = Target { group, lesson, name: g.name.clone(), original: l.clone() };
+
+    let mut week = self.week();
+    // the lesson leaves its place, which only the editor's own day has
+    if let Some(day) = week.iter_mut().find(|d| Some(d.day) == self.editor.loaded_day()) {
+      day.groups[group].lessons.remove(lesson);
+    }
+    let classrooms = occupancy::classrooms(&week, &self.references.references().classrooms);
+    let slots = slots::find(&week, &target.name, &target.original, &classrooms);
+
+    self.slots.set(target, slots);
+    self.screen = Screen::Slots;
+    Ok(None)
+  }
+
+  // Whether the editor still has the lesson a tool was opened for at the same place
+  fn unchanged(&self, group: usize, lesson: usize, name: &str, original: &DefaultLesson) -> bool {
+    self
+      .editor
+      .lesson(group, lesson)
+      .is_some_and(|(g, l)| g.name == name && diff::describe(l) == diff::describe(original))
+  }
+
+  fn move_to_slot(&mut self, idx: usize, confirmed: bool) -> anyhow::Result<Option<String>> {
+    let (Some(target), Some(slot)) = (self.slots.target().cloned(), self.slots.slot(idx).cloned()) else {
+      return Ok(None);
+    };
+    if !self.unchanged(target.group, target.lesson, &target.name, &target.original) {
+      return Err(anyhow!(tr(Key::SlotTargetChanged)));
+    }
+
+    let day = self.editor.snapshot().day;
+    if slot.day != day && !confirmed {
+      self.slots.ask(idx);
+      return Ok(None);
+    }
+
+    let place = format!("{}, {}", map_weekday_to_str(slot.day), slot.num);
+    if slot.day == day {
+      self.slots.clear();
+      self.screen = Screen::Editor;
+      self
+        .editor
+        .move_lesson(target.group, target.lesson, slot.num, slot.is_even, slot.classroom);
+      return Ok(Some(trf(Key::Moved, &[&place])));
+    }
+
+    // Written first, so a failed write leaves the editor as it was
+    let mut lesson = target.original.clone();
+    (lesson.num, lesson.is_even, lesson.classroom) = (slot.num, slot.is_even, slot.classroom);
+    self.move_between_defaults(slot.day, &target.name, &target.original, lesson)?;
+    self.editor.take_lesson(target.group, target.lesson);
+    self.slots.clear();
+    self.screen = Screen::Editor;
+    Ok(Some(trf(Key::MovedToDay, &[&place, &map_weekday_to_str(day), &map_weekday_to_str(slot.day)])))
+  }
+
+  fn find_substitutes(&mut self) -> anyhow::Result<Option<String>> {
+    let mut targets = self.editor.selection();
+    if let (true, Some((group, Some(lesson)))) = (targets.is_empty(), self.editor.cursor()) {
+      targets.push((group, lesson));
+    }
+    if targets.is_empty() {
+      return Err(anyhow!(tr(Key::NothingSelected)));
+    }
+
+    let (week, day) = (self.week(), self.editor.snapshot().day);
+    let substitutions = targets
+      .into_iter()
+      .filter_map(|(group, lesson)| {
+        let (g, l) = self.editor.lesson(group, lesson)?;
+        let candidates = substitution::candidates(&week, day, l);
+        Some(Substitution {
+          group,
+          lesson,
+          name: g.name.clone(),
+          original: l.clone(),
+          chosen: candidates.first().cloned(),
+          candidates,
+        })
+      })
+      .collect();
+    self.substitution.set(day, substitutions);
+    self.screen = Screen::Substitution;
+    Ok(None)
+  }
+
+  fn substitute(&mut self, in_changes: bool) -> anyhow::Result<Option<String>> {
+    let chosen = self
+      .substitution
+      .chosen()
+      .filter(|(s, _)| self.unchanged(s.group, s.lesson, &s.name, &s.original))
+      .map(|(s, teacher)| (s.clone(), teacher.to_string()))
+      .collect::<Vec<_>>();
+    if chosen.is_empty() {
+      return Err(anyhow!(tr(Key::NoSubstitutesChosen)));
+    }
+
+    let day = self.substitution.day();
+    // the nearest date with the editor's weekday, today included
+    let date = Local::now()
+      .date_naive()
+      .iter_days()
+      .find(|d| d.weekday() == day)
+      .unwrap();
+    if in_changes && self.changes.date() != date && self.changes.is_dirty() {
+      return Err(anyhow!(trf(Key::UnsavedChanges, &[&self.changes.date().format("%d.%m.%Y")])));
+    }
+
+    self.substitution.clear();
+    if !in_changes {
+      chosen
+        .iter()
+        .for_each(|(s, teacher)| self.editor.set_teacher(s.group, s.lesson, teacher));
+      self.screen = Screen::Editor;
+      return Ok(Some(trf(Key::Substituted, &[&chosen.len()])));
+    }
+
+    if self.changes.date() != date {
+      self.changes.set_date(date);
+    }
+    let count = chosen.len();
+    self.changes.put_lessons(
+      chosen
+        .into_iter()
+        .map(|(s, teacher)| (s.name, DefaultLesson { teacher: Some(teacher), ..s.original }))
+        .collect(),
     );
+    self.screen = Screen::Changes;
+    Ok(Some(trf(Key::SubstitutedInChanges, &[&count, &date.format("%d.%m.%Y")])))
+  }
 
+  fn save_settings(&mut self) -> anyhow::Result<Option<String>> {
+    let settings = self
+      .settings
+      .settings()
+      .ok_or_else(|| anyhow!(tr(Key::SettingsInvalid)))?;
+    let path = settings.save()?;
+    let settings = settings.with_overrides();
+    let reload = settings::get(|s| s.defaults_dir != settings.defaults_dir);
+    self.theme = palette::apply(settings.theme);
+    settings::apply(settings);
+    if reload {
+      self.load_defaults();
+    }
+    Ok(Some(trf(Key::SettingsSaved, &[&path])))
+  }
+
+  fn load_defaults(&mut self) {
+    self.defaults = env::read_defaults();
+    self.dictionary = Dictionary::from_days(&self.defaults);
+    self.references = ReferencesEditor::new(References::load());
+    self.references.refresh(&self.defaults);
+    self.editor.set_rotations(Rotations::load());
+  }
+
+  fn autosave_state(&self) -> String {
+    serde_json::to_string(&(self.editor.snapshot(), self.editor.source())).unwrap_or_default()
+  }
+
+  fn autosave(&mut self) -> Command<AppMessage> {
+    // the old recovery file is kept until the user decides what to do with it
+    let state = self.autosave_state();
+    if self.recovery.is_some() || state == self.autosaved {
+      return Command::none();
+    }
+
+    self.autosaved = state;
+    let recovery = Recovery::new(self.editor.snapshot(), self.editor.source());
+    Command::perform(async move { recovery.save().map(|_| None).map_err(|err| err.to_string()) }, AppMessage::Completed)
+  }
+
+  fn recover(&mut self, restore: bool) -> anyhow::Result<Option<String>> {
+    let Some((recovery, _)) = self.recovery.take() else {
+      return Ok(None);
+    };
+    if !restore {
+      recovery::clear();
+      return Ok(None);
+    }
+
+    self.editor.restore(recovery.snapshot, recovery.source);
+    Ok(Some(tr(Key::Restored).into()))
+  }
+
+  fn restore_revision(&mut self, idx: usize) -> anyhow::Result<Option<String>> {
+    let Some(revision) = self.history.revision(idx) else {
+      return Ok(None);
+    };
+    let snapshot = revision.load()?;
+    let saved_at = revision.saved_at.format("%d.%m.%Y %H:%M:%S").to_string();
+    self
+      .editor
+      .restore(snapshot, Some(revision.path.display().to_string()));
+    self.screen = Screen::Editor;
+    Ok(Some(trf(Key::RevisionRestored, &[&saved_at])))
+  }
+
+  fn import_html() -> Command<AppMessage> {
+    Command::perform(
+      async {
+        let Some(file) = rfd::AsyncFileDialog::new()
+          .add_filter("HTML", &["html", "htm"])
+          .pick_file()
+          .await
+        else {
+          return Ok(None);
+        };
+        let path = file.path().to_path_buf();
+        import::read(&path)
+          .map(|imported| Some((path.display().to_string(), imported)))
+          .map_err(|err| err.to_string())
+      },
+      AppMessage::HtmlImported,
+    )
+  }
+
+  fn load_imported(&mut self, path: String, imported: Imported) -> anyhow::Result<Option<String>> {
+    if self.changes.is_dirty() {
+      return Err(anyhow!(trf(Key::UnsavedChanges, &[&self.changes.date().format("%d.%m.%Y")])));
+    }
+
+    let mut warnings = imported.warnings;
+    let date = match imported.date {
+      Some(date) => date,
+      None => {
+        let date = self.changes.date();
+        warnings.insert(0, trf(Key::ImportNoDate, &[&date.format("%d.%m.%Y")]));
+        date
+      }
+    };
+    if warnings.len() > IMPORT_WARNINGS_LIMIT {
+      let more = trf(Key::AndMore, &[&(warnings.len() - IMPORT_WARNINGS_LIMIT)]);
+      warnings.truncate(IMPORT_WARNINGS_LIMIT);
+      warnings.push(more);
+    }
+    warnings
+      .into_iter()
+      .for_each(|w| self.notifications.push(Notification::error(tr(Key::Warning), w)));
+
+    let count = imported.groups.len();
+    self.changes.load(date, imported.groups);
+    self.screen = Screen::Changes;
+    Ok(Some(trf(Key::ImportedChanges, &[&path, &count])))
+  }
+
+  // Changes still being edited take precedence over the exported ones, so they can be checked before export
+  fn refresh_final(&mut self) {
+    let date = self.final_day.date();
+    let editing = self.changes.snapshot();
+    let shown = match resolve_weekday(date) == date.weekday() {
+      true => date,
+      false => date.succ_opt().unwrap_or(date),
+    };
+    let snapshot = match changes::date(&editing) == shown {
+      true => Some(editing),
+      false => changes::load(shown),
+    };
+    let sources = Sources {
+      default: self.defaults.iter().find(|d| d.day == shown.weekday()),
+      changes: snapshot.as_ref(),
+      rotations: self.editor.rotations(),
+      anchor: settings::get(|s| s.parity_anchor),
+    };
+    self.final_day.refresh(shown, &sources);
+  }
+
+  fn refresh_curriculum(&mut self) {
+    let (anchor, start, end) = settings::get(|s| (s.parity_anchor, s.semester_start, s.semester_end));
+    let result = curriculum::load().map(|(path, requirements)| {
+      let scheduled = curriculum::scheduled(&self.week(), self.editor.rotations(), anchor, start, end);
+      (path, curriculum::compare(&requirements, scheduled))
+    });
+    self.curriculum.set(result.map_err(|err| err.to_string()));
+  }
+
+  fn refresh_rooms(&mut self) {
+    let week = self.week();
+    let known = &self.references.references().classrooms;
+    self.rooms.refresh(&week, known);
+  }
+
+  fn refresh_preview(&mut self) {
+    let preview = match self.search.replacement() {
+      Some((find, replacement, scope)) => {
+        let mut hits = search::preview(self.editor.snapshot(), find, replacement);
+        if scope == Scope::Week {
+          self
+            .defaults
+            .iter()
+            .filter(|day| Some(day.day) != self.editor.loaded_day())
+            .for_each(|day| hits.extend(search::preview(day, find, replacement)));
+        }
+        hits
+      }
+      None => vec![],
+    };
+    self.search.set_preview(preview);
+  }
+
+  // Every day is changed before any file is written, so a failing write leaves the rest unchanged
+  // and the error tells which days were already written. Returns the count and the written days.
+  fn update_defaults(&mut self, mut f: impl FnMut(&mut DefaultDay) -> usize) -> anyhow::Result<(usize, Vec<Weekday>)> {
+    let mut week = self.defaults.clone();
+    let mut count = 0;
+    let changed = week
+      .iter_mut()
+      .enumerate()
+      .filter_map(|(idx, day)| {
+        let updated = f(day);
+        count += updated;
+        (updated > 0).then_some(idx)
+      })
+      .collect::<Vec<_>>();
+
+    let mut written = vec![];
+    let mut result = Ok(());
+    for idx in changed {
+      if let Err(err) = env::write_default(&week[idx]) {
+        result = Err(anyhow!(trf(Key::PartlyWritten, &[&err, &days_list(&written)])));
+        break;
+      }
+      self.defaults[idx] = week[idx].clone();
+      written.push(week[idx].day);
+    }
+
+    self.dictionary = Dictionary::from_days(&self.defaults);
+    self.references.refresh(&self.defaults);
+    result.map(|_| (count, written))
+  }
+
+  fn shortcut(&mut self, shortcut: Shortcut) -> Command<AppMessage> {
+    let message = match shortcut {
+      Shortcut::FocusNext => {
+        self.editor.leave_lesson();
+        return widget::focus_next();
+      }
+      Shortcut::FocusPrevious => {
+        self.editor.leave_lesson();
+        return widget::focus_previous();
+      }
+      Shortcut::Help => {
+        self.show_cheat_sheet = !self.show_cheat_sheet;
+        return Command::none();
+      }
+      _ if self.screen != Screen::Editor => return Command::none(),
+      Shortcut::Export => AppMessage::Export,
+      Shortcut::Import => AppMessage::ImportToday,
+      Shortcut::NewGroup => AppMessage::Editor(EditorMessage::CreateGroup),
+      Shortcut::NewLesson => match self.editor.cursor() {
+        Some((group, _)) => AppMessage::Editor(EditorMessage::Group((group, GroupMessage::CreateLesson))),
+        None => return Command::none(),
+      },
+      Shortcut::RemoveEditedLesson => match self.editor.cursor() {
+        Some((group, Some(lesson))) => {
+          AppMessage::Editor(EditorMessage::Group((group, GroupMessage::Lesson((lesson, LessonMessage::Remove)))))
+        }
+        _ => return Command::none(),
+      },
+    };
+    self.update(message)
+  }
+
+  fn notifications_view(&self) -> iced::Element<'_, AppMessage> {
     let noty_count = self.notifications.len();
     let pad = if noty_count > 0 { 20 } else { 10 };
-    let notifications_container = scrollable(
+    scrollable(
       row(
         self
           .notifications
@@ -126,17 +756,348 @@ impl Sandbox for App {
       .spacing(10)
       .padding([10, 0, pad, 0]),
     )
-    .horizontal_scroll(iced::widget::scrollable::Properties::default().margin(5));
+    .horizontal_scroll(iced::widget::scrollable::Properties::default().margin(5))
+    .into()
+  }
 
-    let content = column![
-      toolbar(),
-      Rule::horizontal(1),
+  fn editor_view(&self) -> iced::Element<'_, AppMessage> {
+    let groups = scrollable(
+      column(
+        self
+          .editor
+          .groups()
+          .enumerate()
+          .filter(|(_, group)| search::matches_group(group, self.search.query()))
+          .map(|(idx, group)| {
+            let options = ViewOptions {
+              focus: self.editor.focus_in(idx),
+              dictionary: Some(&self.dictionary),
+              search: self.search.query(),
+              selected: self.editor.selected_in(idx),
+              selectable: true,
+              parity: true,
+              can_paste: !self.editor.clipboard().is_empty(),
+              invalid: self.editor.invalid_in(idx),
+              patterns: self.editor.patterns_in(idx),
+              gaps: self.editor.gaps_in(idx),
+            };
+            group
+              .view_with(&options)
+              .map(move |msg| AppMessage::Editor(EditorMessage::Group((idx, msg))))
+          })
+          .collect(),
+      )
+      .padding([0, 15]),
+    );
+
+    column![
       container(self.editor.view().map(AppMessage::Editor)).padding([10, 0, 0, 0]),
+      self.search.view().map(AppMessage::Search),
       Rule::horizontal(1),
-      notifications_container,
+      self.notifications_view(),
       container(groups).width(Length::Fill).padding([0, 0, 0, 0])
-    ];
+    ]
+    .into()
+  }
+}
+
+impl Application for App {
+  type Executor = executor::Default;
+  type Message = AppMessage;
+  type Theme = Theme;
+  type Flags = ();
+
+  fn new(_: Self::Flags) -> (Self, Command<Self::Message>) {
+    let mut app = App { settings: SettingsEditor::new(), ..App::default() };
+    app.theme = palette::apply(settings::get(|s| s.theme));
+    app.load_defaults();
+    app.autosaved = app.autosave_state();
+    if let Some(recovery) = Recovery::load() {
+      let changes = diff::diff(&recovery.original(), &recovery.snapshot);
+      match changes.is_empty() {
+        true => recovery::clear(),
+        false => app.recovery = Some((recovery, changes)),
+      }
+    }
+    settings::get(|s| s.validate())
+      .into_iter()
+      .for_each(|(_, err)| app.notifications.push(Notification::error(tr(Key::Settings), err)));
+    (app, Command::none())
+  }
+
+  fn title(&self) -> String {
+    trf(Key::WindowTitle, &[&map_weekday_to_str(self.editor.snapshot().day)])
+  }
+
+  fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
+    println!("Message: {:?}", message);
+    let res = match message {
+      AppMessage::Shortcut(shortcut) => return self.shortcut(shortcut),
+      AppMessage::Copy => return self.copy(),
+      AppMessage::CopyToDay(weekday) => self.copy_to_day(weekday),
+      AppMessage::Editor(m) => {
+        self.editor.update(m);
+        Ok(None)
+      }
+      AppMessage::References(ReferencesMessage::Rename(idx)) => self.rename_reference(idx),
+      AppMessage::References(ReferencesMessage::Save) => {
+        let references = self.references.references().clone();
+        return Command::perform(
+          async move {
+            references
+              .save()
+              .map(|path| Some(trf(Key::ReferencesSaved, &[&path])))
+              .map_err(|err| err.to_string())
+          },
+          AppMessage::Completed,
+        );
+      }
+      AppMessage::References(m) => {
+        self.references.update(m);
+        self.references.refresh(&self.defaults);
+        Ok(None)
+      }
+      AppMessage::Settings(SettingsMessage::Save) => self.save_settings(),
+      AppMessage::Settings(m) => {
+        self.settings.update(m);
+        Ok(None)
+      }
+      AppMessage::Search(SearchMessage::Replace) => self.replace(),
+      AppMessage::Search(m) => {
+        self.search.update(m);
+        Ok(None)
+      }
+      AppMessage::Changes(ChangesMessage::Export) => return Command::perform(self.changes.export(), AppMessage::Completed),
+      AppMessage::Changes(m) => {
+        self.changes.update(m);
+        Ok(None)
+      }
+      AppMessage::Final(m) => {
+        self.final_day.update(m);
+        self.refresh_final();
+        Ok(None)
+      }
+      AppMessage::Teachers(TeachersMessage::Export(format)) => match self.teachers.selected() {
+        Some((teacher, lessons)) => {
+          let (teacher, lessons) = (teacher.to_string(), lessons.to_vec());
+          return Command::perform(
+            async move {
+              pivot::export(&teacher, &lessons, format)
+                .map(|path| Some(trf(Key::TeacherExported, &[&teacher, &path])))
+                .map_err(|err| err.to_string())
+            },
+            AppMessage::Completed,
+          );
+        }
+        None => Ok(None),
+      },
+      AppMessage::Teachers(m) => {
+        self.teachers.update(m);
+        Ok(None)
+      }
+      AppMessage::Rooms(m) => {
+        self.rooms.update(m);
+        self.refresh_rooms();
+        Ok(None)
+      }
+      AppMessage::FindSlots => self.find_slots(),
+      AppMessage::Slots(SlotsMessage::Apply(idx)) => self.move_to_slot(idx, false),
+      AppMessage::Slots(SlotsMessage::Confirm(idx)) => self.move_to_slot(idx, true),
+      AppMessage::Slots(m) => {
+        self.slots.update(m);
+        Ok(None)
+      }
+      AppMessage::FindSubstitutes => self.find_substitutes(),
+      AppMessage::Substitution(SubstitutionMessage::ApplyToEditor) => self.substitute(false),
+      AppMessage::Substitution(SubstitutionMessage::ToChanges) => self.substitute(true),
+      AppMessage::Substitution(m) => {
+        self.substitution.update(m);
+        Ok(None)
+      }
+      AppMessage::Stats(StatsMessage::Export) => {
+        let current = self.stats.stats().clone();
+        return Command::perform(
+          async move {
+            stats::export(&current)
+              .map(|path| Some(trf(Key::StatsExported, &[&path])))
+              .map_err(|err| err.to_string())
+          },
+          AppMessage::Completed,
+        );
+      }
+      AppMessage::Gaps(m) => {
+        self.gaps.update(m);
+        Ok(None)
+      }
+      AppMessage::Curriculum(CurriculumMessage::Reload) => {
+        self.refresh_curriculum();
+        Ok(None)
+      }
+      AppMessage::Curriculum(m) => {
+        self.curriculum.update(m);
+        Ok(None)
+      }
+      AppMessage::History(HistoryMessage::Restore(idx)) => self.restore_revision(idx),
+      AppMessage::History(m) => {
+        self.history.update(m);
+        Ok(None)
+      }
+      AppMessage::Open(screen) => {
+        match screen {
+          Screen::History => self.history.refresh(),
+          Screen::Final => self.refresh_final(),
+          Screen::Teachers => self.teachers.refresh(&self.week()),
+          Screen::Rooms => self.refresh_rooms(),
+          Screen::Stats => self.stats.refresh(&self.week()),
+          Screen::Gaps => self.gaps.refresh(&self.week()),
+          Screen::Curriculum => self.refresh_curriculum(),
+          _ => (),
+        }
+        self.screen = screen;
+        Ok(None)
+      }
+      AppMessage::Import(idx) => match self.defaults.get(idx) {
+        Some(day) => self.editor.set_groups(day),
+        None => Err(anyhow!(trf(Key::NoDefaultAt, &[&idx]))),
+      },
+      AppMessage::ImportToday => {
+        find_default(&self.defaults, resolve_weekday(now_date())).and_then(|d| self.editor.set_groups(d))
+      }
+      AppMessage::ImportNext => {
+        find_default(&self.defaults, resolve_weekday(now_date_offset(1))).and_then(|d| self.editor.set_groups(d))
+      }
+      AppMessage::ImportHtml => return Self::import_html(),
+      AppMessage::HtmlImported(res) => match res {
+        Ok(Some((path, imported))) => self.load_imported(path, imported),
+        Ok(None) => Ok(None),
+        Err(err) => Err(anyhow!(err)),
+      },
+      AppMessage::Sort(order) => self.editor.sort(order),
+      AppMessage::Export => {
+        let export = self.editor.export();
+        return Command::perform(
+          async move {
+            let res = export.await;
+            if res.is_ok() {
+              recovery::clear();
+            }
+            res
+          },
+          AppMessage::Completed,
+        );
+      }
+      AppMessage::Autosave => return self.autosave(),
+      AppMessage::Recover(restore) => self.recover(restore),
+      AppMessage::Completed(res) => res.map_err(|err| anyhow!(err)),
+      AppMessage::Tick(now) => {
+        self.notifications.retain(|n| !n.expired(now));
+        Ok(None)
+      }
+      AppMessage::DeleteNotification(idx) => {
+        self.notifications.remove(idx);
+        Ok(None)
+      }
+      AppMessage::Nothing => Ok(None),
+      _ => Err(anyhow!(tr(Key::NotImplemented))),
+    };
+
+    self.refresh_preview();
+
+    if let Err(err) = &res {
+      self
+        .notifications
+        .push(Notification::error(tr(Key::Error), err.to_string()));
+      eprintln!("{}", err);
+    }
+
+    if let Ok(Some(ok)) = &res {
+      self.notifications.push(Notification::ok(tr(Key::Info), ok));
+    }
+
+    Command::none()
+  }
+
+  fn theme(&self) -> Self::Theme {
+    self.theme.clone()
+  }
+
+  fn subscription(&self) -> Subscription<Self::Message> {
+    let mut subscriptions =
+      vec![shortcuts::subscription().map(AppMessage::Shortcut), time::every(AUTOSAVE_INTERVAL).map(|_| AppMessage::Autosave)];
+    if self.notifications.iter().any(|n| n.level == Level::Ok) {
+      subscriptions.push(time::every(Duration::from_secs(1)).map(AppMessage::Tick));
+    }
+    Subscription::batch(subscriptions)
+  }
+
+  fn view(&self) -> iced::Element<'_, Self::Message> {
+    let body = match self.screen {
+      Screen::Editor => self.editor_view(),
+      Screen::References => column![self.notifications_view(), self.references.view().map(AppMessage::References)].into(),
+      Screen::Changes => column![self.notifications_view(), self.changes.view().map(AppMessage::Changes)].into(),
+      Screen::Final => column![self.notifications_view(), self.final_day.view().map(AppMessage::Final)].into(),
+      Screen::Teachers => column![self.notifications_view(), self.teachers.view().map(AppMessage::Teachers)].into(),
+      Screen::Rooms => column![self.notifications_view(), self.rooms.view().map(AppMessage::Rooms)].into(),
+      Screen::Slots => column![self.notifications_view(), self.slots.view().map(AppMessage::Slots)].into(),
+      Screen::Substitution => column![self.notifications_view(), self.substitution.view().map(AppMessage::Substitution)].into(),
+      Screen::Stats => column![self.notifications_view(), self.stats.view().map(AppMessage::Stats)].into(),
+      Screen::Gaps => column![self.notifications_view(), self.gaps.view().map(AppMessage::Gaps)].into(),
+      Screen::Curriculum => column![self.notifications_view(), self.curriculum.view().map(AppMessage::Curriculum)].into(),
+      Screen::History => column![self.notifications_view(), self.history.view().map(AppMessage::History)].into(),
+      Screen::Settings => column![self.notifications_view(), self.settings.view().map(AppMessage::Settings)].into(),
+    };
+
+    let content = column![toolbar(&self.defaults), Rule::horizontal(1), body];
+
+    // the recovery prompt can only be answered with its buttons
+    let dismiss = match self.recovery {
+      Some(_) => AppMessage::Nothing,
+      None => AppMessage::Shortcut(Shortcut::Help),
+    };
+    let overlay = move || match &self.recovery {
+      Some((recovery, changes)) => recovery_prompt(recovery, changes),
+      None => cheat_sheet(),
+    };
+    Modal::new(self.show_cheat_sheet || self.recovery.is_some(), container(content).padding(5), overlay)
+      .backdrop(dismiss.clone())
+      .on_esc(dismiss)
+      .into()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use maiq_shared::default::DefaultGroup;
+
+  use super::*;
+
+  fn monday() -> DefaultDay {
+    let lesson = DefaultLesson {
+      num: 1,
+      name: "Математика".into(),
+      subgroup: None,
+      teacher: Some("Иванова А.А.".into()),
+      classroom: Some("204".into()),
+      is_even: None,
+    };
+    DefaultDay { day: Weekday::Mon, groups: vec![DefaultGroup { name: "Ир1-21".into(), lessons: vec![lesson] }] }
+  }
+
+  #[test]
+  fn week_keeps_the_saved_monday_before_anything_is_loaded() {
+    let app = App { defaults: vec![monday()], ..App::default() };
+    let week = app.week();
+    assert_eq!(week.len(), 1);
+    assert_eq!(week[0].groups[0].name, "Ир1-21");
+  }
 
-    container(content).padding(5).into()
+  #[test]
+  fn week_takes_the_loaded_day_from_the_editor() {
+    let mut app = App { defaults: vec![monday()], ..App::default() };
+    app.editor.set_groups(&monday()).unwrap();
+    app.editor.update(EditorMessage::CreateGroup);
+    let week = app.week();
+    assert_eq!(week.len(), 1);
+    assert_eq!(week[0].groups.len(), 2);
   }
 }
@@ -0,0 +1,84 @@
+use std::{fs, path::PathBuf};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use maiq_shared::{
+  default::{DefaultGroup, DefaultLesson},
+  Group, Lesson, Snapshot, Uid,
+};
+
+use crate::env;
+
+// The day's actual changes, in the same format maiq publishes: groups listed here override their default lessons for that date
+pub fn snapshot(date: NaiveDate, groups: &[DefaultGroup]) -> Snapshot {
+  let groups = groups
+    .iter()
+    .map(|g| {
+      let mut group = Group { uid: String::new(), name: g.name.clone(), lessons: g.lessons.iter().map(to_lesson).collect() };
+      group.uid = group.uid();
+      group
+    })
+    .collect();
+  let mut snapshot = Snapshot::new(groups, to_utc(date));
+  snapshot.uid = snapshot.uid();
+  snapshot
+}
+
+pub fn date(snapshot: &Snapshot) -> NaiveDate {
+  snapshot.date.date_naive()
+}
+
+pub fn load(date: NaiveDate) -> Option<Snapshot> {
+  let content = fs::read_to_string(path(date)).ok()?;
+  serde_json::from_str(&content)
+    .map_err(|err| eprintln!("warn -> can't parse changes for {}: {}", date, err))
+    .ok()
+}
+
+pub fn save(snapshot: &Snapshot) -> anyhow::Result<String> {
+  let path = path(date(snapshot));
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir)?;
+  }
+  fs::write(&path, serde_json::to_string_pretty(snapshot)?)?;
+  Ok(path.display().to_string())
+}
+
+pub fn groups(snapshot: &Snapshot) -> Vec<DefaultGroup> {
+  snapshot
+    .groups
+    .iter()
+    .map(|g| DefaultGroup { name: g.name.clone(), lessons: g.lessons.iter().map(to_default).collect() })
+    .collect()
+}
+
+pub fn path(date: NaiveDate) -> PathBuf {
+  PathBuf::from(env::export_dir())
+    .join("changes")
+    .join(format!("{}.json", date.format("%Y-%m-%d")))
+}
+
+fn to_utc(date: NaiveDate) -> DateTime<Utc> {
+  DateTime::<Utc>::from_utc(NaiveDateTime::new(date, NaiveTime::default()), Utc)
+}
+
+fn to_lesson(lesson: &DefaultLesson) -> Lesson {
+  Lesson {
+    num: Some(lesson.num),
+    name: lesson.name.clone(),
+    subgroup: lesson.subgroup,
+    teacher: lesson.teacher.clone(),
+    classroom: lesson.classroom.clone(),
+  }
+}
+
+// A change is for one date, so it has no parity of its own
+pub fn to_default(lesson: &Lesson) -> DefaultLesson {
+  DefaultLesson {
+    num: lesson.num.unwrap_or_default(),
+    name: lesson.name.clone(),
+    subgroup: lesson.subgroup,
+    teacher: lesson.teacher.clone(),
+    classroom: lesson.classroom.clone(),
+    is_even: None,
+  }
+}
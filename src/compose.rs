@@ -0,0 +1,98 @@
+use chrono::{Datelike, NaiveDate};
+use maiq_shared::{
+  default::{DefaultDay, DefaultLesson},
+  Snapshot,
+};
+
+use crate::{
+  changes,
+  ordering::natural_cmp,
+  rotation::{self, Rotations},
+};
+
+#[derive(Debug, Clone)]
+pub struct FinalLesson {
+  pub lesson: DefaultLesson,
+  pub changed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct FinalGroup {
+  pub name: String,
+  pub lessons: Vec<FinalLesson>,
+  pub changed: bool,
+}
+
+pub struct Sources<'a> {
+  pub default: Option<&'a DefaultDay>,
+  pub changes: Option<&'a Snapshot>,
+  pub rotations: &'a Rotations,
+  pub anchor: NaiveDate,
+}
+
+// Default lessons that take place this week, then the day's changes on top: a changed lesson
+// replaces the default lesson with the same number and subgroup, and one without a name cancels it
+pub fn compose(date: NaiveDate, sources: &Sources, subgroup: Option<u8>) -> Vec<FinalGroup> {
+  let week = rotation::week_number(date, sources.anchor);
+  let even = rotation::is_even_week(week);
+  let day = date.weekday();
+
+  let mut groups = sources
+    .default
+    .filter(|d| d.day == day)
+    .map(|d| d.groups.as_slice())
+    .unwrap_or_default()
+    .iter()
+    .map(|g| FinalGroup {
+      name: g.name.clone(),
+      lessons: g
+        .lessons
+        .iter()
+        .filter(|l| match sources.rotations.get(day, &g.name, l) {
+          Some(pattern) => pattern.occurs(week, date),
+          None => l.is_even.is_none_or(|is_even| is_even == even),
+        })
+        .map(|l| FinalLesson { lesson: l.clone(), changed: false })
+        .collect(),
+      changed: false,
+    })
+    .collect::<Vec<_>>();
+
+  for change in sources
+    .changes
+    .filter(|c| changes::date(c) == date)
+    .map(|c| c.groups.as_slice())
+    .unwrap_or_default()
+  {
+    let idx = match groups.iter().position(|g| g.name == change.name) {
+      Some(idx) => idx,
+      None => {
+        groups.push(FinalGroup { name: change.name.clone(), lessons: vec![], changed: true });
+        groups.len() - 1
+      }
+    };
+    let group = &mut groups[idx];
+    group.changed = true;
+    let lessons = change.lessons.iter().map(changes::to_default).collect::<Vec<_>>();
+    group.lessons.retain(|l| {
+      !lessons
+        .iter()
+        .any(|c| (c.num, c.subgroup) == (l.lesson.num, l.lesson.subgroup))
+    });
+    group.lessons.extend(
+      lessons
+        .into_iter()
+        .filter(|l| !l.name.trim().is_empty())
+        .map(|lesson| FinalLesson { lesson, changed: true }),
+    );
+  }
+
+  for group in groups.iter_mut() {
+    group
+      .lessons
+      .retain(|l| subgroup.is_none() || l.lesson.subgroup.is_none() || l.lesson.subgroup == subgroup);
+    group.lessons.sort_by_key(|l| (l.lesson.num, l.lesson.subgroup));
+  }
+  groups.sort_by(|a, b| natural_cmp(&a.name, &b.name));
+  groups
+}
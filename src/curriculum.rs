@@ -0,0 +1,267 @@
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  fs,
+  path::PathBuf,
+};
+
+use anyhow::anyhow;
+use chrono::{Datelike, NaiveDate};
+use maiq_shared::default::DefaultDay;
+use serde::Deserialize;
+
+use crate::{
+  compose::{self, FinalLesson, Sources},
+  env,
+  i18n::{tr, trf, Key},
+  ordering::natural_cmp,
+  rotation::Rotations,
+};
+
+// Academic hours in one lesson ("пара")
+pub const LESSON_HOURS: f32 = 2.;
+
+// One line of the curriculum: hours a group must get in a subject over the semester
+#[derive(Debug, Clone, Deserialize)]
+pub struct Requirement {
+  pub group: String,
+  pub subject: String,
+  pub hours: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+  Under,
+  Matches,
+  Over,
+}
+
+#[derive(Debug, Clone)]
+pub struct Row {
+  pub group: String,
+  pub subject: String,
+  pub required: f32,
+  pub scheduled: f32,
+}
+
+impl Row {
+  pub fn difference(&self) -> f32 {
+    self.scheduled - self.required
+  }
+
+  // A semester rarely divides evenly into weeks, so being off by less than a lesson still matches
+  pub fn status(&self) -> Status {
+    match self.difference() {
+      d if d <= -LESSON_HOURS => Status::Under,
+      d if d >= LESSON_HOURS => Status::Over,
+      _ => Status::Matches,
+    }
+  }
+}
+
+fn subject_key(subject: &str) -> String {
+  subject.trim().to_lowercase()
+}
+
+// `curriculum.json` (an array of requirements) or `curriculum.csv` (group, subject, hours) in the defaults directory
+pub fn load() -> anyhow::Result<(String, Vec<Requirement>)> {
+  let dir = PathBuf::from(env::defaults_dir());
+  let json = dir.join("curriculum.json");
+  let csv = dir.join("curriculum.csv");
+  if let Ok(content) = fs::read_to_string(&json) {
+    let requirements = serde_json::from_str(&content)?;
+    return Ok((json.display().to_string(), requirements));
+  }
+  match fs::read_to_string(&csv) {
+    Ok(content) => Ok((csv.display().to_string(), parse_csv(&content)?)),
+    Err(_) => Err(anyhow!(trf(Key::NoCurriculum, &[&dir.display()]))),
+  }
+}
+
+fn split(line: &str, delimiter: char) -> Vec<String> {
+  let mut fields = vec![String::new()];
+  let mut quoted = false;
+  let mut chars = line.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '"' if quoted && chars.peek() == Some(&'"') => {
+        chars.next();
+        fields.last_mut().unwrap().push('"');
+      }
+      '"' => quoted = !quoted,
+      c if c == delimiter && !quoted => fields.push(String::new()),
+      c => fields.last_mut().unwrap().push(c),
+    }
+  }
+  fields.into_iter().map(|f| f.trim().to_string()).collect()
+}
+
+// Spreadsheets in the Russian locale save `;`-separated files with decimal commas; a header line is skipped
+pub fn parse_csv(content: &str) -> anyhow::Result<Vec<Requirement>> {
+  let content = content.trim_start_matches('\u{feff}');
+  let delimiter = match content.lines().next().is_some_and(|l| l.contains(';')) {
+    true => ';',
+    false => ',',
+  };
+
+  let mut requirements = vec![];
+  for (idx, line) in content.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+    // spreadsheets pad rows with empty cells up to the widest one
+    let mut fields = split(line, delimiter);
+    while fields.len() > 3 && fields.last().is_some_and(|f| f.is_empty()) {
+      fields.pop();
+    }
+    let [group, subject, hours] = fields.as_slice() else {
+      return Err(anyhow!(trf(Key::CurriculumLine, &[&(idx + 1), &tr(Key::CurriculumColumns)])));
+    };
+    match hours.replace(',', ".").parse::<f32>() {
+      Ok(hours) => requirements.push(Requirement { group: group.clone(), subject: subject.clone(), hours }),
+      Err(_) if idx == 0 => continue,
+      Err(_) => return Err(anyhow!(trf(Key::CurriculumLine, &[&(idx + 1), &trf(Key::NotHours, &[hours])]))),
+    }
+  }
+  Ok(requirements)
+}
+
+// Lessons of each subject a student of the group attends that day: every subgroup is counted
+// on its own together with the whole group's lessons, and the busiest subgroup is taken
+fn attended(lessons: &[FinalLesson]) -> BTreeMap<String, (String, usize)> {
+  let subgroups = lessons
+    .iter()
+    .filter_map(|l| l.lesson.subgroup)
+    .collect::<BTreeSet<_>>();
+  let views = match subgroups.is_empty() {
+    true => vec![None],
+    false => subgroups.into_iter().map(Some).collect(),
+  };
+
+  let mut attended = BTreeMap::<String, (String, usize)>::new();
+  for subgroup in views {
+    let mut nums = BTreeMap::<String, (String, BTreeSet<u8>)>::new();
+    lessons
+      .iter()
+      .map(|l| &l.lesson)
+      .filter(|l| l.subgroup.is_none() || l.subgroup == subgroup)
+      .filter(|l| !l.name.trim().is_empty())
+      .for_each(|l| {
+        let (_, set) = nums
+          .entry(subject_key(&l.name))
+          .or_insert_with(|| (l.name.trim().to_string(), BTreeSet::new()));
+        set.insert(l.num);
+      });
+    for (key, (name, set)) in nums {
+      let entry = attended.entry(key).or_insert((name, 0));
+      entry.1 = entry.1.max(set.len());
+    }
+  }
+  attended
+}
+
+// Hours per (group, subject key) the weekly defaults give over `start..=end`, with parity and rotations;
+// one-off changes aren't counted. The subject's name is kept as it's first spelled.
+pub fn scheduled(
+  days: &[DefaultDay],
+  rotations: &Rotations,
+  anchor: NaiveDate,
+  start: NaiveDate,
+  end: NaiveDate,
+) -> BTreeMap<(String, String), (String, f32)> {
+  let mut hours = BTreeMap::<(String, String), (String, f32)>::new();
+  for date in start.iter_days().take_while(|d| *d <= end) {
+    let sources = Sources { default: days.iter().find(|d| d.day == date.weekday()), changes: None, rotations, anchor };
+    for group in compose::compose(date, &sources, None) {
+      for (key, (name, count)) in attended(&group.lessons) {
+        hours
+          .entry((group.name.trim().to_string(), key))
+          .or_insert((name, 0.))
+          .1 += count as f32 * LESSON_HOURS;
+      }
+    }
+  }
+  hours
+}
+
+// Every required subject, then subjects scheduled for a curriculum group that the curriculum lacks
+pub fn compare(requirements: &[Requirement], mut scheduled: BTreeMap<(String, String), (String, f32)>) -> Vec<Row> {
+  let mut required = BTreeMap::<(String, String), (String, f32)>::new();
+  for r in requirements {
+    required
+      .entry((r.group.trim().to_string(), subject_key(&r.subject)))
+      .or_insert((r.subject.trim().to_string(), 0.))
+      .1 += r.hours;
+  }
+  let groups = required
+    .keys()
+    .map(|(group, _)| group.clone())
+    .collect::<BTreeSet<_>>();
+
+  let mut rows = required
+    .into_iter()
+    .map(|((group, key), (subject, required))| Row {
+      scheduled: scheduled
+        .remove(&(group.clone(), key))
+        .map(|(_, h)| h)
+        .unwrap_or_default(),
+      group,
+      subject,
+      required,
+    })
+    .collect::<Vec<_>>();
+  rows.extend(
+    scheduled
+      .into_iter()
+      .filter(|((group, _), _)| groups.contains(group))
+      .map(|((group, _), (subject, scheduled))| Row { group, subject, required: 0., scheduled }),
+  );
+  rows.sort_by(|a, b| natural_cmp(&a.group, &b.group).then_with(|| natural_cmp(&a.subject, &b.subject)));
+  rows
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use super::*;
+
+  fn parsed(content: &str) -> Vec<(String, String, f32)> {
+    parse_csv(content)
+      .unwrap()
+      .into_iter()
+      .map(|r| (r.group, r.subject, r.hours))
+      .collect()
+  }
+
+  #[test]
+  fn reads_the_saved_curriculum() {
+    let content = fs::read_to_string(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/curriculum.csv")).unwrap();
+    assert_eq!(
+      parsed(&content),
+      [
+        ("ИС-21".into(), "Математика".into(), 72.),
+        ("ИС-21".into(), "Иностранный язык".into(), 36.),
+        ("ИС-21".into(), "Физика".into(), 54.5),
+      ]
+    );
+  }
+
+  #[test]
+  fn reads_comma_separated_lines() {
+    assert_eq!(
+      parsed("Группа,Предмет,Часы\nИС-21,\"Математика, алгебра\",72\nИС-21,Физика,54.5\n"),
+      [("ИС-21".into(), "Математика, алгебра".into(), 72.), ("ИС-21".into(), "Физика".into(), 54.5)]
+    );
+  }
+
+  #[test]
+  fn ignores_trailing_empty_fields() {
+    assert_eq!(
+      parsed("ИС-21;Математика;72;;\nИС-21;Физика;54,5;\n"),
+      [("ИС-21".into(), "Математика".into(), 72.), ("ИС-21".into(), "Физика".into(), 54.5)]
+    );
+  }
+
+  #[test]
+  fn reports_the_broken_line() {
+    assert!(parse_csv("ИС-21;Математика;72\nИС-21;Физика\n").is_err());
+    assert!(parse_csv("ИС-21;Математика;72\nИС-21;Физика;много\n").is_err());
+  }
+}
@@ -0,0 +1,116 @@
+use std::collections::BTreeSet;
+
+use maiq_shared::default::{DefaultDay, DefaultLesson};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+  Subject,
+  Teacher,
+  Classroom,
+}
+
+impl Field {
+  pub const ALL: [Field; 3] = [Field::Subject, Field::Teacher, Field::Classroom];
+
+  pub fn get(self, lesson: &DefaultLesson) -> &str {
+    match self {
+      Field::Subject => &lesson.name,
+      Field::Teacher => lesson.teacher.as_deref().unwrap_or_default(),
+      Field::Classroom => lesson.classroom.as_deref().unwrap_or_default(),
+    }
+  }
+
+  pub fn set(self, lesson: &mut DefaultLesson, value: String) {
+    match self {
+      Field::Subject => lesson.name = value,
+      Field::Teacher => lesson.teacher = Some(value),
+      Field::Classroom => lesson.classroom = Some(value),
+    }
+  }
+}
+
+#[derive(Debug, Default)]
+pub struct Dictionary {
+  subjects: BTreeSet<String>,
+  teachers: BTreeSet<String>,
+  classrooms: BTreeSet<String>,
+}
+
+impl Dictionary {
+  pub fn from_days(days: &[DefaultDay]) -> Self {
+    let mut dictionary = Self::default();
+    days
+      .iter()
+      .flat_map(|d| d.groups.iter())
+      .flat_map(|g| g.lessons.iter())
+      .for_each(|l| dictionary.insert(l));
+    dictionary
+  }
+
+  pub fn insert(&mut self, lesson: &DefaultLesson) {
+    for field in Field::ALL {
+      let value = field.get(lesson).trim();
+      if !value.is_empty() {
+        self.entries_mut(field).insert(value.to_string());
+      }
+    }
+  }
+
+  pub fn suggest(&self, field: Field, query: &str, limit: usize) -> Vec<String> {
+    let query = normalize(query);
+    if query.is_empty() {
+      return vec![];
+    }
+
+    let mut matches = self
+      .entries(field)
+      .iter()
+      .filter_map(|entry| score(&query, &normalize(entry)).map(|score| (score, entry)))
+      .collect::<Vec<_>>();
+    matches.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)));
+    matches
+      .into_iter()
+      .take(limit)
+      .map(|(_, entry)| entry.clone())
+      .collect()
+  }
+
+  fn entries(&self, field: Field) -> &BTreeSet<String> {
+    match field {
+      Field::Subject => &self.subjects,
+      Field::Teacher => &self.teachers,
+      Field::Classroom => &self.classrooms,
+    }
+  }
+
+  fn entries_mut(&mut self, field: Field) -> &mut BTreeSet<String> {
+    match field {
+      Field::Subject => &mut self.subjects,
+      Field::Teacher => &mut self.teachers,
+      Field::Classroom => &mut self.classrooms,
+    }
+  }
+}
+
+// "Иванов И.И" and "иванов и. и." should be treated as the same entry
+fn normalize(value: &str) -> String {
+  value
+    .chars()
+    .filter(|c| c.is_alphanumeric())
+    .flat_map(char::to_lowercase)
+    .map(|c| if c == 'ё' { 'е' } else { c })
+    .collect()
+}
+
+fn score(query: &str, entry: &str) -> Option<u8> {
+  if entry == query {
+    Some(0)
+  } else if entry.starts_with(query) {
+    Some(1)
+  } else if entry.contains(query) {
+    Some(2)
+  } else {
+    let mut chars = entry.chars();
+    query.chars().all(|q| chars.any(|c| c == q)).then_some(3)
+  }
+}
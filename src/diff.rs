@@ -0,0 +1,93 @@
+use std::fmt::Display;
+
+use maiq_shared::default::{DefaultDay, DefaultLesson};
+
+use crate::i18n::{tr, trf, Key};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+  AddedGroup(String),
+  RemovedGroup(String),
+  AddedLesson { group: String, lesson: String },
+  RemovedLesson { group: String, lesson: String },
+  ChangedLesson { group: String, before: String, after: String },
+}
+
+impl Display for Change {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let line = match self {
+      Change::AddedGroup(group) => trf(Key::DiffAddedGroup, &[group]),
+      Change::RemovedGroup(group) => trf(Key::DiffRemovedGroup, &[group]),
+      Change::AddedLesson { group, lesson } => trf(Key::DiffAddedLesson, &[group, lesson]),
+      Change::RemovedLesson { group, lesson } => trf(Key::DiffRemovedLesson, &[group, lesson]),
+      Change::ChangedLesson { group, before, after } => trf(Key::DiffChangedLesson, &[group, before, after]),
+    };
+    write!(f, "{}", line)
+  }
+}
+
+pub fn describe(lesson: &DefaultLesson) -> String {
+  let mut out = match lesson.subgroup {
+    Some(subgroup) => format!("{}/{} {}", lesson.num, subgroup, lesson.name),
+    None => format!("{} {}", lesson.num, lesson.name),
+  };
+  for value in [&lesson.teacher, &lesson.classroom].into_iter().flatten() {
+    out += ", ";
+    out += value;
+  }
+  match lesson.is_even {
+    Some(true) => out += &format!(", {}", tr(Key::Even).to_lowercase()),
+    Some(false) => out += &format!(", {}", tr(Key::Odd).to_lowercase()),
+    None => (),
+  }
+  out
+}
+
+// Lessons are matched by (num, subgroup), in order, so swapping two lessons of the same slot is a change
+pub fn diff(before: &DefaultDay, after: &DefaultDay) -> Vec<Change> {
+  let mut changes = vec![];
+  for group in before.groups.iter() {
+    if !after.groups.iter().any(|g| g.name == group.name) {
+      changes.push(Change::RemovedGroup(group.name.clone()));
+    }
+  }
+
+  for group in after.groups.iter() {
+    let Some(old) = before.groups.iter().find(|g| g.name == group.name) else {
+      changes.push(Change::AddedGroup(group.name.clone()));
+      continue;
+    };
+
+    let mut slots = old
+      .lessons
+      .iter()
+      .chain(group.lessons.iter())
+      .map(|l| (l.num, l.subgroup))
+      .collect::<Vec<_>>();
+    slots.sort();
+    slots.dedup();
+
+    for (num, subgroup) in slots {
+      let in_slot = |lessons: &[DefaultLesson]| {
+        lessons
+          .iter()
+          .filter(|l| l.num == num && l.subgroup == subgroup)
+          .map(describe)
+          .collect::<Vec<_>>()
+      };
+      let (was, now) = (in_slot(&old.lessons), in_slot(&group.lessons));
+      for idx in 0..was.len().max(now.len()) {
+        let name = group.name.clone();
+        match (was.get(idx), now.get(idx)) {
+          (Some(before), Some(after)) if before != after => {
+            changes.push(Change::ChangedLesson { group: name, before: before.clone(), after: after.clone() })
+          }
+          (Some(lesson), None) => changes.push(Change::RemovedLesson { group: name, lesson: lesson.clone() }),
+          (None, Some(lesson)) => changes.push(Change::AddedLesson { group: name, lesson: lesson.clone() }),
+          _ => (),
+        }
+      }
+    }
+  }
+  changes
+}
@@ -1,8 +1,10 @@
 use std::{fs, str::FromStr};
 
-use lazy_static::lazy_static;
+use chrono::Weekday;
 use maiq_shared::default::DefaultDay;
 
+use crate::{format, settings};
+
 pub fn parse_var<T: FromStr>(var: &'static str) -> Option<T> {
   self::var(var).and_then(|x| x.parse().ok())
 }
@@ -11,58 +13,68 @@ pub fn var(var: &'static str) -> Option<String> {
   dotenvy::var(var).ok()
 }
 
-pub fn check<T: FromStr>(var: &'static str) -> bool {
-  parse_var::<T>(var)
-    .is_none()
-    .then(|| println!("Var {}: {} is not present", var, std::any::type_name::<T>().split("::").last().unwrap()))
-    .is_none()
+pub const WEEKDAYS: [Weekday; 6] = [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat];
+
+pub fn default_path(day: Weekday) -> String {
+  format!("{}/{}.json", defaults_dir(), day.to_string().to_lowercase())
+}
+
+pub fn references_path() -> String {
+  format!("{}/references.json", defaults_dir())
+}
+
+pub fn read_defaults() -> Vec<DefaultDay> {
+  fn read(path: &String) -> Option<DefaultDay> {
+    fs::read_to_string(path).ok().and_then(|content| {
+      format::from_str(&content)
+        .map_err(|err| eprintln!("warn -> can't parse default timetable from `{}`: {}", path, err))
+        .ok()
+    })
+  }
+
+  WEEKDAYS
+    .into_iter()
+    .map(|day| {
+      let path = default_path(day);
+      (read(&path), path)
+    })
+    .filter(|(f, path)| match f {
+      Some(_) => true,
+      None => {
+        eprintln!("warn -> no default found in {}", path);
+        false
+      }
+    })
+    .map(|f| f.0.unwrap())
+    .collect::<Vec<DefaultDay>>()
 }
 
-lazy_static! {
-  pub static ref DEFAULTS: Vec<DefaultDay> = {
-    fn read(path: &String) -> Option<DefaultDay> {
-      fs::read_to_string(path).ok().map(|content| {
-        serde_json::from_str(content.as_str()).unwrap_or_else(|_| panic!("Can't parse default timetable from `{}`", &path))
-      })
-    }
+pub fn write_default(day: &DefaultDay) -> anyhow::Result<String> {
+  let path = default_path(day.day);
+  fs::write(&path, format::to_string(day)?)?;
+  Ok(path)
+}
 
-    ["mon", "tue", "wed", "thu", "fri", "sat"]
-      .iter()
-      .map(|f| {
-        let path = format!("default/{}.json", f);
-        (read(&path), path)
-      })
-      .filter(|(f, path)| match f {
-        Some(_) => true,
-        None => {
-          eprintln!("warn -> no default found in {}", path);
-          false
-        }
-      })
-      .map(|f| f.0.unwrap())
-      .collect::<Vec<DefaultDay>>()
+pub fn init() {
+  print!("Reading .env.. ");
+  match dotenvy::dotenv() {
+    Ok(_) => println!("ok"),
+    Err(err) => println!("{}", err),
   };
 }
 
-macro_rules! vars {
-  [$($var_name: ident: $getter: ident -> $ty: tt),*] => {
-    $(const $var_name: &'static str = stringify!($var_name);
+pub fn export_dir() -> String {
+  settings::get(|s| s.export_dir.clone())
+}
 
-    pub fn $getter() -> $ty {
-      parse_var($var_name).unwrap()
-    })*
+pub fn defaults_dir() -> String {
+  settings::get(|s| s.defaults_dir.clone())
+}
 
-    pub fn init() {
-      print!("Reading .env.. ");
-      match dotenvy::dotenv() {
-        Ok(_) => println!("ok"),
-        Err(err) => println!("{}", err)
-      };
-      let mut failed = false;
-      $(failed |= !check::<$ty>($var_name);)*
-      failed.then(|| panic!("Not all .env args are set") );
-    }
-  };
+pub fn max_lesson_num() -> u8 {
+  settings::get(|s| s.max_lesson_num)
 }
 
-vars![EXPORT_DIRECTORY: export_dir -> String];
+pub fn max_subgroups() -> u8 {
+  settings::get(|s| s.max_subgroups)
+}
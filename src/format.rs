@@ -0,0 +1,171 @@
+use std::fs;
+
+use anyhow::{anyhow, bail};
+use maiq_shared::default::DefaultDay;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::i18n::{trf, Key};
+
+pub const FORMAT: &str = "maiq-default";
+pub const VERSION: u64 = 2;
+pub const SCHEMA_FILE: &str = "default.schema.json";
+
+// MIGRATIONS[n] upgrades a version n + 1 document to version n + 2
+const MIGRATIONS: [fn(Value) -> anyhow::Result<Value>; 1] = [from_raw_day];
+
+#[derive(Serialize)]
+struct Envelope<'a> {
+  format: &'static str,
+  version: u64,
+  timetable: &'a DefaultDay,
+}
+
+pub fn to_string(day: &DefaultDay) -> serde_json::Result<String> {
+  serde_json::to_string_pretty(&Envelope { format: FORMAT, version: VERSION, timetable: day })
+}
+
+pub fn from_str(content: &str) -> anyhow::Result<DefaultDay> {
+  let mut value = serde_json::from_str::<Value>(content)?;
+  let version = version_of(&value)?;
+  if version > VERSION {
+    bail!(trf(Key::NewerFormat, &[&version, &VERSION]));
+  }
+  for migrate in MIGRATIONS.iter().skip(version as usize - 1) {
+    value = migrate(value)?;
+  }
+  Ok(serde_json::from_value(value["timetable"].take())?)
+}
+
+// Files written before the envelope are a bare `DefaultDay`, which is version 1
+fn version_of(value: &Value) -> anyhow::Result<u64> {
+  match value.get("format").and_then(Value::as_str) {
+    Some(FORMAT) => value
+      .get("version")
+      .and_then(Value::as_u64)
+      .filter(|v| *v > 0)
+      .ok_or_else(|| anyhow!(trf(Key::UnknownFormat, &[&FORMAT]))),
+    Some(other) => bail!(trf(Key::UnknownFormat, &[&other])),
+    None if value.get("groups").is_some() => Ok(1),
+    None => bail!(trf(Key::UnknownFormat, &[&"?"])),
+  }
+}
+
+// v1 -> v2: wrap into the envelope; lessons saved by the optionable-num parser may have no number,
+// those continue the numbering of the previous lesson
+fn from_raw_day(mut day: Value) -> anyhow::Result<Value> {
+  for group in day["groups"].as_array_mut().into_iter().flatten() {
+    let mut prev = 0;
+    for lesson in group["lessons"].as_array_mut().into_iter().flatten() {
+      let num = match lesson.get("num").and_then(Value::as_u64) {
+        Some(num) => num,
+        None => prev + 1,
+      };
+      lesson["num"] = num.into();
+      prev = num;
+    }
+  }
+  Ok(json!({ "format": FORMAT, "version": 2, "timetable": day }))
+}
+
+pub fn schema() -> Value {
+  let optional = |kind: &str| json!({ "type": [kind, "null"] });
+  json!({
+    "$schema": "https://json-schema.org/draft/2020-12/schema",
+    "$id": format!("{}-v{}", FORMAT, VERSION),
+    "title": "maiq default timetable",
+    "type": "object",
+    "required": ["format", "version", "timetable"],
+    "properties": {
+      "format": { "const": FORMAT },
+      "version": { "const": VERSION },
+      "timetable": {
+        "type": "object",
+        "required": ["day", "groups"],
+        "properties": {
+          "day": { "enum": ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"] },
+          "groups": {
+            "type": "array",
+            "items": {
+              "type": "object",
+              "required": ["name", "lessons"],
+              "properties": {
+                "name": { "type": "string" },
+                "lessons": {
+                  "type": "array",
+                  "items": {
+                    "type": "object",
+                    "required": ["num", "name"],
+                    "properties": {
+                      "num": { "type": "integer", "minimum": 1, "maximum": 255 },
+                      "name": { "type": "string" },
+                      "subgroup": { "type": ["integer", "null"], "minimum": 1, "maximum": 255 },
+                      "teacher": optional("string"),
+                      "classroom": optional("string"),
+                      "is_even": optional("boolean")
+                    }
+                  }
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+  })
+}
+
+pub fn write_schema(dir: &str) -> anyhow::Result<()> {
+  fs::write(format!("{}/{}", dir, SCHEMA_FILE), serde_json::to_string_pretty(&schema())?)?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use chrono::Weekday;
+
+  use super::*;
+
+  fn fixture(name: &str) -> String {
+    fs::read_to_string(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(name)).unwrap()
+  }
+
+  #[test]
+  fn loads_a_bare_v1_day() {
+    let day = from_str(&fixture("default_v1.json")).unwrap();
+    assert_eq!(day.day, Weekday::Mon);
+    let lessons = &day.groups[0].lessons;
+    assert_eq!(lessons.iter().map(|l| l.num).collect::<Vec<_>>(), [2, 3, 5]);
+    assert_eq!(lessons[1].name, "Базы данных");
+    assert_eq!((lessons[1].subgroup, lessons[1].is_even), (Some(1), Some(true)));
+    assert_eq!(lessons[2].teacher, None);
+  }
+
+  #[test]
+  fn loads_an_enveloped_v2_day() {
+    let day = from_str(&fixture("default_v2.json")).unwrap();
+    assert_eq!(day.day, Weekday::Thu);
+    assert_eq!(day.groups[0].name, "С2-22");
+    let lessons = &day.groups[0].lessons;
+    assert_eq!(lessons.iter().map(|l| l.num).collect::<Vec<_>>(), [1, 3]);
+    assert_eq!(lessons[1].teacher.as_deref(), Some("Смирнова Е.В."));
+    assert_eq!((lessons[1].subgroup, lessons[1].is_even), (Some(2), Some(true)));
+  }
+
+  #[test]
+  fn saves_what_it_loads() {
+    let day = from_str(&fixture("default_v1.json")).unwrap();
+    let saved = to_string(&day).unwrap();
+    assert_eq!(version_of(&serde_json::from_str(&saved).unwrap()).unwrap(), VERSION);
+    assert_eq!(to_string(&from_str(&saved).unwrap()).unwrap(), saved);
+  }
+
+  #[test]
+  fn refuses_newer_and_unknown_formats() {
+    assert!(from_str(r#"{ "format": "maiq-default", "version": 3, "timetable": {} }"#).is_err());
+    assert!(from_str(r#"{ "format": "other", "version": 1 }"#).is_err());
+    assert!(from_str(r#"{ "day": "Mon" }"#).is_err());
+  }
+}
@@ -0,0 +1,188 @@
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  fmt::Display,
+};
+
+use chrono::Weekday;
+use maiq_shared::default::{DefaultDay, DefaultGroup, DefaultLesson};
+
+use crate::{
+  i18n::{tr, trf, Key},
+  occupancy::WeekParity,
+  references::Kind,
+};
+
+// Idle lessons `from..=to` in a group's or a teacher's day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gap {
+  pub kind: Kind,
+  pub name: String,
+  pub day: Weekday,
+  // `None` is the whole group
+  pub subgroup: Option<u8>,
+  // `None` is every week
+  pub week: Option<WeekParity>,
+  pub from: u8,
+  pub to: u8,
+}
+
+impl Gap {
+  pub fn size(&self) -> usize {
+    self.to as usize - self.from as usize + 1
+  }
+}
+
+impl Display for Gap {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let mut out = match self.from == self.to {
+      true => trf(Key::GapAt, &[&self.from]),
+      false => trf(Key::GapRange, &[&self.from, &self.to]),
+    };
+    if let Some(subgroup) = self.subgroup {
+      out += &format!(", {}", trf(Key::SubgroupN, &[&subgroup]).to_lowercase());
+    }
+    if let Some(week) = self.week {
+      out += &format!(", {}", week.to_string().to_lowercase());
+    }
+    write!(f, "{}", out)
+  }
+}
+
+// Idle lessons between the first and the last one
+pub fn count(nums: &BTreeSet<u8>) -> usize {
+  match (nums.first(), nums.last()) {
+    (Some(first), Some(last)) => (*last as usize - *first as usize + 1) - nums.len(),
+    _ => 0,
+  }
+}
+
+fn runs(nums: &BTreeSet<u8>) -> Vec<(u8, u8)> {
+  nums
+    .iter()
+    .zip(nums.iter().skip(1))
+    .filter(|(a, b)| **b > **a + 1)
+    .map(|(a, b)| (a + 1, b - 1))
+    .collect()
+}
+
+// Runs found in every subgroup (or in both weeks) are reported once for the whole group (or every week)
+fn analyze(kind: Kind, name: &str, day: Weekday, views: &[(Option<u8>, Vec<&DefaultLesson>)]) -> Vec<Gap> {
+  let mut found = BTreeMap::<(u8, u8), BTreeMap<Option<u8>, Vec<WeekParity>>>::new();
+  for (subgroup, lessons) in views {
+    for week in WeekParity::ALL {
+      let nums = lessons
+        .iter()
+        .filter(|l| week.includes(l))
+        .map(|l| l.num)
+        .collect::<BTreeSet<_>>();
+      for run in runs(&nums) {
+        found.entry(run).or_default().entry(*subgroup).or_default().push(week);
+      }
+    }
+  }
+
+  let mut gaps = vec![];
+  for ((from, to), subgroups) in found {
+    let weeks = |weeks: &Vec<WeekParity>| match weeks.len() == WeekParity::ALL.len() {
+      true => vec![None],
+      false => weeks.iter().map(|w| Some(*w)).collect(),
+    };
+    let whole =
+      views.len() > 1 && subgroups.len() == views.len() && subgroups.values().all(|w| Some(w) == subgroups.values().next());
+    let entries = match whole {
+      true => weeks(subgroups.values().next().unwrap())
+        .into_iter()
+        .map(|week| (None, week))
+        .collect::<Vec<_>>(),
+      false => subgroups
+        .iter()
+        .flat_map(|(subgroup, w)| weeks(w).into_iter().map(move |week| (*subgroup, week)))
+        .collect(),
+    };
+    gaps.extend(
+      entries
+        .into_iter()
+        .map(|(subgroup, week)| Gap { kind, name: name.to_string(), day, subgroup, week, from, to }),
+    );
+  }
+  gaps
+}
+
+// A subgroup's students attend their own lessons and the whole group's ones
+pub fn group_gaps(day: Weekday, group: &DefaultGroup) -> Vec<Gap> {
+  let subgroups = group
+    .lessons
+    .iter()
+    .filter_map(|l| l.subgroup)
+    .collect::<BTreeSet<_>>();
+  let views: Vec<(Option<u8>, Vec<&DefaultLesson>)> = match subgroups.is_empty() {
+    true => vec![(None, group.lessons.iter().collect())],
+    false => subgroups
+      .into_iter()
+      .map(|s| {
+        let lessons = group
+          .lessons
+          .iter()
+          .filter(|l| l.subgroup.is_none() || l.subgroup == Some(s))
+          .collect();
+        (Some(s), lessons)
+      })
+      .collect(),
+  };
+  analyze(Kind::Group, &group.name, day, &views)
+}
+
+pub fn teacher_gaps(day: &DefaultDay) -> Vec<Gap> {
+  let mut teachers = BTreeMap::<&str, Vec<&DefaultLesson>>::new();
+  day.groups.iter().flat_map(|g| g.lessons.iter()).for_each(|l| {
+    if let Some(teacher) = l.teacher.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+      teachers.entry(teacher).or_default().push(l);
+    }
+  });
+  teachers
+    .into_iter()
+    .flat_map(|(teacher, lessons)| analyze(Kind::Teacher, teacher, day.day, &[(None, lessons)]))
+    .collect()
+}
+
+// Every gap of the week, largest first
+pub fn report(days: &[DefaultDay]) -> Vec<Gap> {
+  let mut gaps = days
+    .iter()
+    .flat_map(|day| {
+      day
+        .groups
+        .iter()
+        .flat_map(|g| group_gaps(day.day, g))
+        .chain(teacher_gaps(day))
+    })
+    .collect::<Vec<_>>();
+  gaps.sort_by_key(|g| (std::cmp::Reverse(g.size()), g.day.num_days_from_monday(), g.from));
+  gaps
+}
+
+pub fn summary(gaps: &[Gap], kind: Kind) -> String {
+  let count = gaps.iter().filter(|g| g.kind == kind).count();
+  let total = gaps.iter().filter(|g| g.kind == kind).map(Gap::size).sum::<usize>();
+  match count {
+    0 => tr(Key::NoGaps).to_string(),
+    _ => trf(Key::GapsSummary, &[&count, &total]),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn counts_idle_lessons_between_the_first_and_the_last() {
+    assert_eq!(count(&BTreeSet::new()), 0);
+    assert_eq!(count(&BTreeSet::from([2, 3, 6])), 2);
+  }
+
+  #[test]
+  fn counts_the_whole_range_of_numbers() {
+    assert_eq!(count(&BTreeSet::from([0, 255])), 254);
+    assert_eq!(runs(&BTreeSet::from([0, 255])), [(1, 254)]);
+  }
+}
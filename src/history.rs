@@ -0,0 +1,59 @@
+use std::{fs, path::PathBuf};
+
+use chrono::{Local, NaiveDateTime, Weekday};
+use maiq_shared::default::DefaultDay;
+
+use crate::{env, format};
+
+const STAMP_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+#[derive(Debug, Clone)]
+pub struct Revision {
+  pub saved_at: NaiveDateTime,
+  pub path: PathBuf,
+}
+
+impl Revision {
+  pub fn load(&self) -> anyhow::Result<DefaultDay> {
+    format::from_str(&fs::read_to_string(&self.path)?)
+  }
+}
+
+fn day_dir(export_dir: &str, day: Weekday) -> PathBuf {
+  PathBuf::from(export_dir)
+    .join("history")
+    .join(day.to_string().to_lowercase())
+}
+
+pub fn record(export_dir: &str, snapshot: &DefaultDay) -> anyhow::Result<String> {
+  let dir = day_dir(export_dir, snapshot.day);
+  fs::create_dir_all(&dir)?;
+  let path = dir.join(format!("{}.json", Local::now().format(STAMP_FORMAT)));
+  fs::write(&path, format::to_string(snapshot)?)?;
+  Ok(path.display().to_string())
+}
+
+// Newest first
+pub fn list(day: Weekday) -> Vec<Revision> {
+  let Ok(entries) = fs::read_dir(day_dir(&env::export_dir(), day)) else {
+    return vec![];
+  };
+  let mut revisions = entries
+    .filter_map(|entry| {
+      let path = entry.ok()?.path();
+      let saved_at = NaiveDateTime::parse_from_str(path.file_stem()?.to_str()?, STAMP_FORMAT).ok()?;
+      Some(Revision { saved_at, path })
+    })
+    .collect::<Vec<_>>();
+  revisions.sort_by(|a, b| b.saved_at.cmp(&a.saved_at));
+  revisions
+}
+
+// The last exported version, or an empty day if the day was never exported
+pub fn current(day: Weekday) -> DefaultDay {
+  let path = format!("{}/{}.json", env::export_dir(), day.to_string().to_lowercase());
+  fs::read_to_string(path)
+    .ok()
+    .and_then(|content| format::from_str(&content).ok())
+    .unwrap_or(DefaultDay { day, groups: vec![] })
+}
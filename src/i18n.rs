@@ -0,0 +1,309 @@
+use std::fmt::Display;
+
+use crate::settings::{self, Language};
+
+// Every key has to be given both translations, and `lookup` matches on (key, language) exhaustively,
+// so a missing string or a new language without a catalog is a compile error
+macro_rules! messages {
+  [$($key: ident => $ru: literal, $en: literal;)*] => {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Key {
+      $($key),*
+    }
+
+    #[cfg(test)]
+    const KEYS: &[Key] = &[$(Key::$key),*];
+
+    fn lookup(key: Key, language: Language) -> &'static str {
+      match (key, language) {
+        $((Key::$key, Language::Russian) => $ru,
+        (Key::$key, Language::English) => $en,)*
+      }
+    }
+  };
+}
+
+messages![
+  Monday => "Понедельник", "Monday";
+  Tuesday => "Вторник", "Tuesday";
+  Wednesday => "Среда", "Wednesday";
+  Thursday => "Четверг", "Thursday";
+  Friday => "Пятница", "Friday";
+  Saturday => "Суббота", "Saturday";
+  Sunday => "Воскресенье", "Sunday";
+
+  WindowTitle => "maiq-client — {}", "maiq-client — {}";
+  File => "Файл", "File";
+  New => "Новый", "New";
+  Import => "Импорт", "Import";
+  Export => "Экспорт", "Export";
+  Standard => "Стандартное", "Default";
+  Today => "Сегодня", "Today";
+  Tomorrow => "Завтра", "Tomorrow";
+  Edit => "Редактировать", "Edit";
+  Copy => "Копировать", "Copy";
+  CopyTo => "Копировать в", "Copy to";
+  Sorting => "Сортировка", "Sorting";
+  View => "Вид", "View";
+  Editor => "Редактор", "Editor";
+  References => "Справочники", "References";
+  Settings => "Настройки", "Settings";
+  Server => "Сервер", "Server";
+  ApiToken => "API токен", "API token";
+  Publish => "Опубликовать", "Publish";
+
+  Save => "Сохранить", "Save";
+  Reset => "Сбросить", "Reset";
+  Value => "Значение", "Value";
+  NewValue => "Новое значение", "New value";
+  NotInReferences => "Не найдены в справочнике", "Not found in references";
+  Teachers => "Преподаватели", "Teachers";
+  Subjects => "Предметы", "Subjects";
+  Classrooms => "Аудитории", "Classrooms";
+  Groups => "Группы", "Groups";
+
+  SortNatural => "Естественная", "Natural";
+  SortAlphabetical => "По алфавиту", "Alphabetical";
+  ScopeDay => "Текущий день", "Current day";
+  ScopeWeek => "Вся неделя", "Whole week";
+
+  Shortcuts => "Горячие клавиши", "Keyboard shortcuts";
+  ShortcutImport => "Импорт стандартного на сегодня", "Import today's default";
+  ShortcutNewGroup => "Новая группа", "New group";
+  ShortcutNewLesson => "Новая пара в текущей группе", "New lesson in the current group";
+  ShortcutRemoveEditedLesson => "Удалить последнюю изменённую пару", "Remove the last edited lesson";
+  ShortcutFocusNext => "Следующее поле", "Next field";
+  ShortcutFocusPrevious => "Предыдущее поле", "Previous field";
+  ShortcutHelp => "Показать / скрыть подсказку", "Show / hide this help";
+
+  Error => "Ошибка!", "Error!";
+  Info => "Инфо", "Info";
+  NotImplemented => "Не реализовано", "Not implemented";
+  NoDefaultFor => "Нет стандартного расписания на {}", "No default timetable for {}";
+  NoDefaultAt => "Нет стандартного расписания #{}", "No default timetable #{}";
+  Renamed => "Переименовано {} → {}: {}, записаны: {}", "Renamed {} → {}: {}, written: {}";
+  Replaced => "Заменено: {}, записаны: {}", "Replaced: {}, written: {}";
+  PartlyWritten => "{}; уже записаны: {}", "{}; already written: {}";
+  NothingSelected => "Нет выделенных пар", "No lessons selected";
+  Copied => "Скопировано пар: {}", "Lessons copied: {}";
+  CopiedTo => "Скопировано пар: {} → {}", "Lessons copied: {} → {}";
+  SettingsInvalid => "Настройки содержат ошибки", "Settings contain errors";
+  SettingsSaved => "Настройки сохранены в {}", "Settings saved to {}";
+  ReferencesSaved => "Справочники сохранены в {}", "References saved to {}";
+
+  GroupCopy => "{} (копия)", "{} (copy)";
+  Sorted => "Отсортировано: {}", "Sorted: {}";
+  Exported => "Экспортировано в {}", "Exported to {}";
+  ExportedApproximated => "Экспортировано в {}. Особых чередований записано приблизительно: {} (по чётности или как «Всегда»)",
+    "Exported to {}. Custom rotations approximated: {} (by parity or as “Always”)";
+  Loaded => "Загружен: {}", "Loaded: {}";
+  DayLabel => "День: {}", "Day: {}";
+  Group => "Группа", "Group";
+
+  Search => "Поиск", "Search";
+  ReplaceMode => "Замена", "Replace";
+  ReplaceWith => "Заменить на", "Replace with";
+  ReplaceAction => "Заменить", "Replace";
+  HitLesson => "{}, {}, пара {}", "{}, {}, lesson {}";
+  AndMore => "… и ещё {}", "… and {} more";
+
+  Subject => "Предмет", "Subject";
+  Teacher => "Преподаватель", "Teacher";
+  ClassroomShort => "Ауд.", "Room";
+  PatternPlaceholder => "1,3/4 или 1,3/м", "1,3/4 or 1,3/m";
+  NumRequired => "Укажите номер пары", "Enter a lesson number";
+  NumRange => "Номер пары от 1 до {}", "Lesson number must be from 1 to {}";
+  SubgroupRange => "Подгруппа от 1 до {}", "Subgroup must be from 1 to {}";
+  NotANumber => "`{}` — не число", "`{}` is not a number";
+  Every => "Всегда", "Always";
+  Even => "Чётная", "Even";
+  Odd => "Нечётная", "Odd";
+  Custom => "Особая", "Custom";
+
+  MonthCycle => "м", "m";
+  PatternFormat => "Формат: 1,3/4 или 1,3/м", "Format: 1,3/4 or 1,3/m";
+  NotAWeek => "`{}` — не номер недели", "`{}` is not a week number";
+  NotACycle => "`{}` — не длина цикла", "`{}` is not a cycle length";
+  CycleTooShort => "Цикл минимум из 2 недель", "A cycle needs at least 2 weeks";
+  WeekOutOfRange => "Неделя {} вне диапазона 1..{}", "Week {} is outside 1..{}";
+
+  Theme => "Тема", "Theme";
+  Language => "Язык", "Language";
+  ThemeLight => "Светлая", "Light";
+  ThemeDark => "Тёмная", "Dark";
+  ThemeSystem => "Как в системе", "System";
+  ExportDir => "Папка экспорта", "Export directory";
+  DefaultsDir => "Папка стандартного расписания", "Default timetable directory";
+  ServerUrl => "Адрес сервера", "Server URL";
+  ParityAnchor => "Начало чётности недель", "Week parity start";
+  SemesterStart => "Начало семестра", "Semester start";
+  SemesterEnd => "Конец семестра", "Semester end";
+  SemesterEndsBeforeStart => "Семестр заканчивается раньше, чем начинается", "The semester ends before it starts";
+  MaxLessonNum => "Максимальный номер пары", "Max lesson number";
+  MaxSubgroups => "Максимум подгрупп", "Max subgroups";
+  DatePlaceholder => "ГГГГ-ММ-ДД", "YYYY-MM-DD";
+  DateFormat => "Формат: ГГГГ-ММ-ДД", "Format: YYYY-MM-DD";
+  ExpectedNumber => "Ожидалось число", "Expected a number";
+  SettingsFile => "Файл настроек: {}", "Settings file: {}";
+  OverriddenByEnv => "Переопределено в .env: {}", "Overridden in .env: {}";
+  ExportDirRequired => "Укажите папку экспорта", "Enter an export directory";
+  DirNotFound => "Папка `{}` не найдена", "Directory `{}` not found";
+  UrlScheme => "Адрес должен начинаться с http:// или https://", "URL must start with http:// or https://";
+  OneTo => "От 1 до {}", "From 1 to {}";
+
+  RecoveryFound => "Найдены несохранённые изменения", "Unsaved changes found";
+  AutosavedAt => "Автосохранение от {}", "Autosaved at {}";
+  OriginalFile => "Исходный файл: {}", "Original file: {}";
+  NewFile => "Новый файл", "New file";
+  Restore => "Восстановить", "Restore";
+  Discard => "Отбросить", "Discard";
+  Restored => "Несохранённые изменения восстановлены", "Unsaved changes restored";
+  DiffAddedGroup => "+ группа {}", "+ group {}";
+  DiffRemovedGroup => "− группа {}", "− group {}";
+  DiffAddedLesson => "+ {}: пара {}", "+ {}: lesson {}";
+  DiffRemovedLesson => "− {}: пара {}", "− {}: lesson {}";
+  DiffChangedLesson => "~ {}: пара {} → {}", "~ {}: lesson {} → {}";
+
+  History => "История", "History";
+  Changes => "Изменения", "Changes";
+  ChangesFor => "Изменения на", "Changes for";
+  ChangesExported => "Изменения на {} экспортированы в {}", "Changes for {} exported to {}";
+  Compare => "Сравнить", "Compare";
+  NoRevisions => "Нет сохранённых ревизий", "No revisions saved";
+  NoChanges => "Изменений нет", "No changes";
+  ChangesSince => "Изменения с {} до текущей версии", "Changes from {} to the current version";
+  NewerFormat => "Файл создан более новой версией формата (v{}, поддерживается до v{})",
+    "The file uses a newer format version (v{}, up to v{} is supported)";
+  UnknownFormat => "Неизвестный формат файла: {}", "Unknown file format: {}";
+  RevisionRestored => "Ревизия от {} загружена в редактор", "Revision from {} loaded into the editor";
+
+  ImportHtml => "Изменения из HTML…", "Changes from HTML…";
+  ImportedChanges => "Из {} загружено групп: {}", "Groups loaded from {}: {}";
+  ImportNoDate => "Дата не найдена в файле, изменения загружены на {}", "No date found in the file, changes loaded for {}";
+  ImportNoGroup => "Строка без группы: {}", "Row without a group: {}";
+  ImportNoNum => "{}: не удалось разобрать номер пары: {}", "{}: can't parse the lesson number: {}";
+  ImportBadSubgroup => "{}: не удалось разобрать подгруппу: {}", "{}: can't parse the subgroup: {}";
+  ImportNoTable => "Таблица изменений не найдена (строк в файле: {})", "No changes table found ({} rows in the file)";
+  Warning => "Предупреждение", "Warning";
+
+  TeacherFilter => "Фильтр по имени", "Filter by name";
+  SelectTeacher => "Выберите преподавателя", "Select a teacher";
+  TeacherExported => "Расписание {} сохранено в {}", "Timetable of {} saved to {}";
+  Day => "День", "Day";
+  Num => "Пара", "Lesson";
+  Parity => "Неделя", "Week";
+
+  Occupancy => "Занятость аудиторий", "Classroom occupancy";
+  FreeRoomsAt => "Свободные аудитории на паре", "Free classrooms at lesson";
+  FreeRooms => "Свободно {}: {}", "{} free: {}";
+  NoFreeRooms => "Свободных аудиторий нет", "No free classrooms";
+
+  FindSlots => "Найти время для пары", "Find a slot for the lesson";
+  NoSlotTarget => "Поставьте курсор на пару, которую нужно перенести", "Put the cursor on the lesson to move";
+  SlotTargetChanged => "Пара изменилась в редакторе, найдите время заново", "The lesson changed in the editor, search again";
+  SlotsFor => "Куда перенести: {}, {}", "Where to move: {}, {}";
+  NoSlots => "Нет времени, когда свободны группа, преподаватель и аудитория",
+    "No slot where the group, the teacher and a classroom are all free";
+  NewGaps => "Новых окон: {}", "New gaps: {}";
+  MoveHere => "Перенести", "Move here";
+  Moved => "Пара перенесена: {}", "Lesson moved: {}";
+  MovedToDay => "Пара перенесена: {}; сохранённые расписания на {} и {} обновлены",
+    "Lesson moved: {}; the saved timetables for {} and {} are updated";
+  ConfirmMoveToDay => "Перенос на {} сразу запишет {} и уберёт пару из сохранённого расписания этого дня. Перенести?",
+    "Moving to {} writes {} right away and removes the lesson from this day's saved timetable. Move?";
+  Cancel => "Отмена", "Cancel";
+
+  FindSubstitutes => "Замена преподавателя", "Substitute a teacher";
+  SubstitutionsFor => "Замены на {}", "Substitutions for {}";
+  CandidateLoad => "{} (пар в этот день: {})", "{} ({} lessons that day)";
+  Substitute => "Кто заменит", "Substitute";
+  NoCandidates => "Некому заменить", "Nobody is free to substitute";
+  ApplyToEditor => "Применить в редакторе", "Apply in the editor";
+  ToChanges => "В изменения", "To changes";
+  UnsavedChanges => "Изменения на {} не экспортированы: сначала экспортируйте их", "Changes for {} aren't exported: export them first";
+  ConfirmDiscardChanges => "Изменения на {} не экспортированы и пропадут. Открыть {}?", "Changes for {} aren't exported and will be lost. Open {}?";
+  DiscardChanges => "Открыть без экспорта", "Open without exporting";
+  NoSubstitutesChosen => "Не выбрано ни одной замены", "No substitutes chosen";
+  Substituted => "Заменено пар: {}", "Lessons substituted: {}";
+  SubstitutedInChanges => "Замены ({}) добавлены в изменения на {}", "Substitutions ({}) added to the changes for {}";
+
+  Statistics => "Статистика", "Statistics";
+  LessonsPerWeek => "Пар в неделю (чётные и нечётные считаются за половину)", "Lessons per week (even and odd ones count as half)";
+  ClassroomUtilization => "Загрузка аудиторий", "Classroom utilization";
+  DayLoad => "Пар по дням", "Lessons per day";
+  Section => "Раздел", "Section";
+  Name => "Название", "Name";
+  StatsExported => "Статистика сохранена в {}", "Statistics saved to {}";
+
+  Gaps => "Окна", "Gaps";
+  GapAt => "окно: {} пара", "gap: lesson {}";
+  GapRange => "окно: {}–{} пары", "gap: lessons {}–{}";
+  NoGaps => "Окон нет", "No gaps";
+  GapsSummary => "Окон: {}, пустых пар: {}", "Gaps: {}, idle lessons: {}";
+  WorstGaps => "Самые большие окна", "Largest gaps";
+
+  Curriculum => "Учебный план", "Curriculum";
+  CurriculumSource => "{}, семестр {} — {}; пара — {} ч., изменения не учитываются", "{}, semester {} — {}; a lesson is {} h, changes aren't counted";
+  NoCurriculum => "В {} нет curriculum.json или curriculum.csv", "No curriculum.json or curriculum.csv in {}";
+  CurriculumLine => "Учебный план, строка {}: {}", "Curriculum, line {}: {}";
+  CurriculumColumns => "ожидаются группа, предмет и часы", "expected a group, a subject and hours";
+  NotHours => "«{}» — не число часов", "`{}` is not a number of hours";
+  OnlyMismatches => "Только расхождения", "Only mismatches";
+  Reload => "Перечитать", "Reload";
+  Required => "План", "Required";
+  Scheduled => "В расписании", "Scheduled";
+  Difference => "Разница", "Difference";
+
+  FinalView => "Итоговое расписание", "Final timetable";
+  FinalDay => "Итоговое расписание на", "Final timetable for";
+  AllSubgroups => "Все подгруппы", "All subgroups";
+  SubgroupN => "Подгруппа {}", "Subgroup {}";
+  WeekLabel => "Неделя {}, {}", "Week {}, {}";
+  NoLessons => "Пар нет", "No lessons";
+  ShownInstead => "Показано расписание на {}", "Showing the timetable for {}";
+  ChangedLegend => "● — изменено относительно стандартного расписания", "● — changed from the standard timetable";
+];
+
+pub fn tr(key: Key) -> &'static str {
+  lookup(key, settings::get(|s| s.language))
+}
+
+// Substitutes `{}` placeholders of the translation in order
+pub fn trf(key: Key, args: &[&dyn Display]) -> String {
+  let mut out = String::new();
+  for (idx, part) in tr(key).split("{}").enumerate() {
+    if let Some(arg) = idx.checked_sub(1).and_then(|idx| args.get(idx)) {
+      out += &arg.to_string();
+    }
+    out += part;
+  }
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn has_cyrillic(text: &str) -> bool {
+    text.chars().any(|c| matches!(c, 'а'..='я' | 'А'..='Я' | 'ё' | 'Ё'))
+  }
+
+  #[test]
+  fn every_key_is_translated() {
+    for key in KEYS {
+      for language in Language::ALL {
+        assert!(!lookup(*key, language).trim().is_empty(), "{:?} is empty in {}", key, language);
+      }
+      let english = lookup(*key, Language::English);
+      assert!(!has_cyrillic(english), "{:?} isn't translated to English: {}", key, english);
+    }
+  }
+
+  #[test]
+  fn translations_take_the_same_arguments() {
+    for key in KEYS {
+      let counts = Language::ALL.map(|language| lookup(*key, language).matches("{}").count());
+      assert!(counts.iter().all(|c| *c == counts[0]), "{:?} has different placeholders: {:?}", key, counts);
+    }
+  }
+}
@@ -0,0 +1,462 @@
+use std::{fs, path::Path};
+
+use chrono::NaiveDate;
+use maiq_shared::default::{DefaultGroup, DefaultLesson};
+
+use crate::i18n::{trf, Key};
+
+// The same table walk maiq-parser does on the college site, run on a saved page: the client only depends
+// on maiq-shared, and the parser has no entry point for a file. `fixtures/changes.html` shows the layout
+// it expects, the tests pin what a saved page adds (encoding, missing header, spans).
+#[derive(Debug, Clone, Default)]
+pub struct Imported {
+  pub date: Option<NaiveDate>,
+  pub groups: Vec<DefaultGroup>,
+  // Rows and fragments that couldn't be turned into lessons
+  pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+  Group,
+  Subgroup,
+  Num,
+  ByDefault,
+  Subject,
+  Teacher,
+  Classroom,
+}
+
+// Used when the table has no recognizable header row
+const LAYOUT: [Column; 6] = [Column::Group, Column::Subgroup, Column::Num, Column::ByDefault, Column::Subject, Column::Classroom];
+
+// Checked in order: "Подгруппа" also contains "группа"
+const HEADERS: [(&str, Column); 11] = [
+  ("подгр", Column::Subgroup),
+  ("групп", Column::Group),
+  ("по расписанию", Column::ByDefault),
+  ("пара", Column::Num),
+  ("№", Column::Num),
+  ("замена", Column::Subject),
+  ("предмет", Column::Subject),
+  ("дисциплина", Column::Subject),
+  ("преподават", Column::Teacher),
+  ("кабинет", Column::Classroom),
+  ("ауд", Column::Classroom),
+];
+
+const CANCELLED: [&str; 4] = ["нет", "снято", "снята", "отмена"];
+
+// A note row spans the whole table; a wider span is a broken page and would only blow the row up
+const MAX_COLSPAN: usize = 32;
+
+// Pages saved from the college site are usually in windows-1251
+pub fn read(path: &Path) -> anyhow::Result<Imported> {
+  let html = match String::from_utf8(fs::read(path)?) {
+    Ok(html) => html,
+    Err(err) => encoding_rs::WINDOWS_1251.decode(err.as_bytes()).0.into_owned(),
+  };
+  Ok(parse(&html))
+}
+
+pub fn parse(html: &str) -> Imported {
+  let mut imported = Imported { date: find_date(&text_of(html)), ..Imported::default() };
+  let rows = rows(html);
+
+  let header = rows.iter().position(|row| columns(row).is_some());
+  let (layout, body) = match header {
+    Some(idx) => (columns(&rows[idx]).unwrap(), &rows[idx + 1..]),
+    None => (LAYOUT.iter().map(|c| Some(*c)).collect(), &rows[..]),
+  };
+
+  let mut group: Option<String> = None;
+  for row in body.iter().filter(|row| row.iter().any(|cell| !cell.is_empty())) {
+    let cell = |column: Column| {
+      layout
+        .iter()
+        .position(|c| *c == Some(column))
+        .and_then(|idx| row.get(idx))
+        .map(|cell| cell.trim())
+        .filter(|cell| !cell.is_empty())
+    };
+
+    if let Some(name) = cell(Column::Group) {
+      group = Some(name.to_string());
+    }
+    let Some(name) = group.clone() else {
+      imported.warnings.push(trf(Key::ImportNoGroup, &[&fragment(row)]));
+      continue;
+    };
+    let Some(nums) = cell(Column::Num).and_then(parse_nums) else {
+      imported
+        .warnings
+        .push(trf(Key::ImportNoNum, &[&name, &fragment(row)]));
+      continue;
+    };
+    let subgroup = match cell(Column::Subgroup).map(|s| s.chars().filter(char::is_ascii_digit).collect::<String>().parse()) {
+      Some(Ok(subgroup)) => Some(subgroup),
+      Some(Err(_)) => {
+        imported
+          .warnings
+          .push(trf(Key::ImportBadSubgroup, &[&name, &fragment(row)]));
+        None
+      }
+      None => None,
+    };
+
+    // an empty or "снято" replacement cancels the lesson
+    let subject = cell(Column::Subject).filter(|s| !CANCELLED.contains(&s.to_lowercase().as_str()));
+    let (subject, teacher) = match (subject, cell(Column::Teacher)) {
+      (Some(subject), Some(teacher)) => (subject.to_string(), Some(teacher.to_string())),
+      (Some(subject), None) => match subject.rsplit_once(',') {
+        Some((subject, teacher)) => (subject.trim().to_string(), Some(teacher.trim().to_string())),
+        None => (subject.to_string(), None),
+      },
+      (None, _) => (String::new(), None),
+    };
+    let classroom = cell(Column::Classroom).map(String::from);
+
+    let idx = match imported.groups.iter().position(|g| g.name == name) {
+      Some(idx) => idx,
+      None => {
+        imported.groups.push(DefaultGroup { name, lessons: vec![] });
+        imported.groups.len() - 1
+      }
+    };
+    imported.groups[idx]
+      .lessons
+      .extend(nums.into_iter().map(|num| DefaultLesson {
+        num,
+        name: subject.clone(),
+        subgroup,
+        teacher: teacher.clone(),
+        classroom: classroom.clone(),
+        is_even: None,
+      }));
+  }
+
+  if imported.groups.is_empty() && imported.warnings.is_empty() {
+    imported.warnings.push(trf(Key::ImportNoTable, &[&rows.len()]));
+  }
+  imported
+}
+
+fn fragment(row: &[String]) -> String {
+  row
+    .iter()
+    .filter(|cell| !cell.is_empty())
+    .map(String::as_str)
+    .collect::<Vec<_>>()
+    .join(" | ")
+}
+
+fn columns(row: &[String]) -> Option<Vec<Option<Column>>> {
+  let columns = row
+    .iter()
+    .map(|cell| {
+      let cell = cell.to_lowercase();
+      HEADERS
+        .iter()
+        .find(|(word, _)| cell.contains(word))
+        .map(|(_, column)| *column)
+    })
+    .collect::<Vec<_>>();
+  let found = |column| columns.contains(&Some(column));
+  (found(Column::Group) && found(Column::Num)).then_some(columns)
+}
+
+// "3", "3 пара", "1,2", "1-3"
+fn parse_nums(cell: &str) -> Option<Vec<u8>> {
+  let cell = cell.trim_end_matches(|c: char| !c.is_ascii_digit());
+  let nums = match cell.split_once(['-', '–']) {
+    Some((from, to)) => (from.trim().parse().ok()?..=to.trim().parse().ok()?).collect::<Vec<u8>>(),
+    None => cell
+      .split(',')
+      .map(|n| n.trim().parse().ok())
+      .collect::<Option<Vec<u8>>>()?,
+  };
+  (!nums.is_empty()).then_some(nums)
+}
+
+fn find_date(text: &str) -> Option<NaiveDate> {
+  text
+    .split(|c: char| !c.is_ascii_digit() && c != '.')
+    .find_map(|word| NaiveDate::parse_from_str(word.trim_matches('.'), "%d.%m.%Y").ok())
+}
+
+// Every <tr> of the page as cell texts, with rowspan/colspan expanded so that columns line up
+fn rows(html: &str) -> Vec<Vec<String>> {
+  let mut rows = vec![];
+  // column -> (text, rows left)
+  let mut spans: Vec<Option<(String, usize)>> = vec![];
+
+  for tr in elements(html, &["tr"]) {
+    let mut row = vec![];
+    let mut cells = elements(tr, &["td", "th"])
+      .into_iter()
+      .map(|cell| (attr(cell, "rowspan"), attr(cell, "colspan"), text_of(inner(cell))));
+
+    let mut column = 0;
+    loop {
+      if let Some(Some((text, left))) = spans.get_mut(column) {
+        row.push(text.clone());
+        *left -= 1;
+        if *left == 0 {
+          spans[column] = None;
+        }
+        column += 1;
+        continue;
+      }
+      let Some((rowspan, colspan, text)) = cells.next() else {
+        break;
+      };
+      for _ in 0..colspan.clamp(1, MAX_COLSPAN) {
+        if rowspan > 1 {
+          spans.resize(spans.len().max(column + 1), None);
+          spans[column] = Some((text.clone(), rowspan - 1));
+        }
+        row.push(text.clone());
+        column += 1;
+      }
+    }
+    rows.push(row);
+  }
+  rows
+}
+
+// Outer HTML of every element with one of the tags, in document order and without closing tags.
+// Elements left unclosed (as `<td>` often is) end where the next one starts.
+fn elements<'a>(html: &'a str, tags: &[&str]) -> Vec<&'a str> {
+  let lower = html.to_ascii_lowercase();
+  let find = |from: usize, prefix: &str| {
+    tags
+      .iter()
+      .filter_map(|tag| {
+        let pattern = format!("{}{}", prefix, tag);
+        let mut at = from;
+        while let Some(idx) = lower[at..].find(&pattern).map(|idx| idx + at) {
+          let boundary = lower[idx + pattern.len()..].chars().next();
+          if matches!(boundary, Some('>' | ' ' | '\t' | '\n' | '\r' | '/')) {
+            return Some(idx);
+          }
+          at = idx + pattern.len();
+        }
+        None
+      })
+      .min()
+  };
+
+  let mut found = vec![];
+  let mut from = 0;
+  while let Some(start) = find(from, "<") {
+    let next = start + 1;
+    let end = [find(next, "</"), find(next, "<")]
+      .into_iter()
+      .flatten()
+      .min()
+      .unwrap_or(html.len());
+    found.push(&html[start..end]);
+    from = end;
+  }
+  found
+}
+
+fn inner(element: &str) -> &str {
+  element.find('>').map(|idx| &element[idx + 1..]).unwrap_or_default()
+}
+
+fn attr(element: &str, name: &str) -> usize {
+  let tag = &element[..element.find('>').unwrap_or(element.len())].to_ascii_lowercase();
+  tag
+    .find(&format!("{}=", name))
+    .map(|idx| &tag[idx + name.len() + 1..])
+    .and_then(|value| {
+      value
+        .trim_start_matches(['"', '\''])
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+    })
+    .unwrap_or(1)
+}
+
+// Tags dropped, entities decoded, whitespace collapsed
+fn text_of(html: &str) -> String {
+  let mut text = String::new();
+  let mut in_tag = false;
+  for c in html.chars() {
+    match c {
+      '<' => {
+        in_tag = true;
+        text.push(' ');
+      }
+      '>' => in_tag = false,
+      c if !in_tag => text.push(c),
+      _ => (),
+    }
+  }
+  decode(&text).split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn decode(text: &str) -> String {
+  let mut out = String::new();
+  let mut rest = text;
+  while let Some(idx) = rest.find('&') {
+    out += &rest[..idx];
+    rest = &rest[idx..];
+    let Some(end) = rest.find(';').filter(|end| *end <= 8) else {
+      out.push('&');
+      rest = &rest[1..];
+      continue;
+    };
+    let decoded = match &rest[1..end] {
+      "nbsp" => Some(' '),
+      "amp" => Some('&'),
+      "lt" => Some('<'),
+      "gt" => Some('>'),
+      "quot" => Some('"'),
+      "apos" => Some('\''),
+      entity => entity
+        .strip_prefix("#x")
+        .map(|hex| u32::from_str_radix(hex, 16).ok())
+        .unwrap_or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+        .and_then(char::from_u32),
+    };
+    match decoded {
+      Some(c) => {
+        out.push(c);
+        rest = &rest[end + 1..];
+      }
+      None => {
+        out.push('&');
+        rest = &rest[1..];
+      }
+    }
+  }
+  out + rest
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use super::*;
+
+  fn fixture() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/changes.html")
+  }
+
+  fn group<'a>(imported: &'a Imported, name: &str) -> &'a DefaultGroup {
+    imported
+      .groups
+      .iter()
+      .find(|g| g.name == name)
+      .unwrap_or_else(|| panic!("no group {}", name))
+  }
+
+  #[test]
+  fn reads_date_and_groups() {
+    let imported = read(&fixture()).unwrap();
+    assert_eq!(imported.date, NaiveDate::from_ymd_opt(2023, 10, 16));
+    let names = imported.groups.iter().map(|g| g.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["Ир1-21", "С2-22"]);
+  }
+
+  #[test]
+  fn splits_subject_and_teacher() {
+    let imported = read(&fixture()).unwrap();
+    let first = &group(&imported, "Ир1-21").lessons[0];
+    assert_eq!((first.num, first.name.as_str()), (1, "Математика"));
+    assert_eq!(first.teacher.as_deref(), Some("Иванова А.А."));
+    assert_eq!(first.classroom.as_deref(), Some("204"));
+    assert_eq!(first.subgroup, None);
+  }
+
+  #[test]
+  fn spans_group_over_rows_and_numbers() {
+    let imported = read(&fixture()).unwrap();
+    let lessons = &group(&imported, "Ир1-21").lessons;
+    assert_eq!(lessons.iter().map(|l| l.num).collect::<Vec<_>>(), [1, 2, 3, 4]);
+    for lesson in &lessons[1..3] {
+      assert_eq!(lesson.name, "Базы данных");
+      assert_eq!(lesson.subgroup, Some(1));
+      assert_eq!(lesson.teacher.as_deref(), Some("Петров П.П."));
+      assert_eq!(lesson.classroom.as_deref(), Some("Лаб. 3"));
+    }
+  }
+
+  #[test]
+  fn cancelled_lesson_has_no_name() {
+    let imported = read(&fixture()).unwrap();
+    let cancelled = group(&imported, "Ир1-21")
+      .lessons
+      .iter()
+      .find(|l| l.num == 4)
+      .unwrap();
+    assert!(cancelled.name.is_empty());
+    assert_eq!((cancelled.teacher.as_ref(), cancelled.classroom.as_ref()), (None, None));
+  }
+
+  #[test]
+  fn warns_about_colspan_note_row() {
+    let imported = read(&fixture()).unwrap();
+    assert_eq!(imported.warnings.len(), 1);
+    assert!(imported.warnings[0].contains("Классный час перенесён на среду"));
+    let lessons = &group(&imported, "С2-22").lessons;
+    assert_eq!(lessons.len(), 1);
+    assert_eq!((lessons[0].num, lessons[0].name.as_str()), (5, "Физкультура"));
+    assert_eq!(lessons[0].classroom.as_deref(), Some("Спортзал"));
+  }
+
+  #[test]
+  fn reads_windows_1251() {
+    let html = fs::read_to_string(fixture()).unwrap();
+    // tests run in parallel, possibly in several processes
+    let nanos = std::time::SystemTime::now()
+      .duration_since(std::time::UNIX_EPOCH)
+      .unwrap()
+      .as_nanos();
+    let path = std::env::temp_dir().join(format!("maiq-client-changes-1251-{}-{}.html", std::process::id(), nanos));
+    fs::write(&path, encoding_rs::WINDOWS_1251.encode(&html).0).unwrap();
+    let imported = read(&path).unwrap();
+    _ = fs::remove_file(&path);
+    assert_eq!(imported.date, NaiveDate::from_ymd_opt(2023, 10, 16));
+    assert_eq!(group(&imported, "Ир1-21").lessons[0].teacher.as_deref(), Some("Иванова А.А."));
+  }
+
+  #[test]
+  fn reads_table_without_header() {
+    let imported =
+      parse("<p>Изменения на 17.10.2023</p><table><tr><td>Ир1-21<td>2<td>1-2<td>Физика<td>Химия, Кузнецова О.И.<td>101</table>");
+    assert_eq!(imported.date, NaiveDate::from_ymd_opt(2023, 10, 17));
+    let lessons = &group(&imported, "Ир1-21").lessons;
+    assert_eq!(lessons.iter().map(|l| l.num).collect::<Vec<_>>(), [1, 2]);
+    assert_eq!((lessons[0].name.as_str(), lessons[0].subgroup), ("Химия", Some(2)));
+    assert_eq!(lessons[0].teacher.as_deref(), Some("Кузнецова О.И."));
+    assert_eq!(lessons[0].classroom.as_deref(), Some("101"));
+  }
+
+  #[test]
+  fn reads_lesson_numbers() {
+    assert_eq!(parse_nums("3"), Some(vec![3]));
+    assert_eq!(parse_nums("3 пара"), Some(vec![3]));
+    assert_eq!(parse_nums("1,2"), Some(vec![1, 2]));
+    assert_eq!(parse_nums("1–3"), Some(vec![1, 2, 3]));
+    assert_eq!(parse_nums("пара"), None);
+  }
+
+  #[test]
+  fn caps_colspan() {
+    let rows = rows("<table><tr><td colspan=\"4000000000\">Примечание</td></tr><tr><td>1</td></tr></table>");
+    assert_eq!(rows[0].len(), MAX_COLSPAN);
+    assert_eq!(rows[1], ["1"]);
+  }
+
+  #[test]
+  fn warns_without_table() {
+    let imported = parse("<p>Изменений нет</p>");
+    assert!(imported.groups.is_empty());
+    assert_eq!(imported.warnings.len(), 1);
+  }
+}
@@ -1,15 +1,42 @@
 use app::App;
-use iced::{Sandbox, Settings};
+use iced::{window, Application, Settings};
 use include_dir::{include_dir, Dir};
 
 mod app;
+mod changes;
+mod compose;
+mod curriculum;
+mod dictionary;
+mod diff;
 mod env;
+mod format;
+mod gaps;
+mod history;
+mod i18n;
+mod import;
+mod occupancy;
+mod ordering;
+mod pivot;
+mod recovery;
+mod references;
+mod rotation;
+mod search;
+mod settings;
+mod shortcuts;
+mod slots;
+mod stats;
+mod substitution;
 mod view;
 
 static ASSETS: Dir<'static> = include_dir!("$CARGO_MANIFEST_DIR/assets");
 
 fn main() {
   env::init();
+  settings::apply(settings::Settings::load());
   // pretty_env_logger::init();
-  _ = App::run(Settings { default_font: ASSETS.get_file("Roboto.ttf").map(|f| f.contents()), ..Settings::default() });
+  _ = App::run(Settings {
+    default_font: ASSETS.get_file("Roboto.ttf").map(|f| f.contents()),
+    window: window::Settings { size: (1100, 760), min_size: Some((800, 500)), ..window::Settings::default() },
+    ..Settings::default()
+  });
 }
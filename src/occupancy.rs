@@ -0,0 +1,108 @@
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  fmt::Display,
+};
+
+use chrono::Weekday;
+use maiq_shared::default::{DefaultDay, DefaultLesson};
+
+use crate::{
+  i18n::{tr, Key},
+  ordering::natural_cmp,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeekParity {
+  #[default]
+  Odd,
+  Even,
+}
+
+impl WeekParity {
+  pub const ALL: [WeekParity; 2] = [WeekParity::Odd, WeekParity::Even];
+
+  // Lessons without parity take place every week
+  pub fn includes(self, lesson: &DefaultLesson) -> bool {
+    lesson
+      .is_even
+      .is_none_or(|is_even| is_even == (self == WeekParity::Even))
+  }
+}
+
+impl Display for WeekParity {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      WeekParity::Odd => write!(f, "{}", tr(Key::Odd)),
+      WeekParity::Even => write!(f, "{}", tr(Key::Even)),
+    }
+  }
+}
+
+// Two lessons can meet only if they take place in a common week
+pub fn overlaps(a: Option<bool>, b: Option<bool>) -> bool {
+  a.is_none() || b.is_none() || a == b
+}
+
+#[derive(Debug, Clone)]
+pub struct Occupant {
+  pub group: String,
+  pub subgroup: Option<u8>,
+}
+
+impl Display for Occupant {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self.subgroup {
+      Some(subgroup) => write!(f, "{}/{}", self.group, subgroup),
+      None => write!(f, "{}", self.group),
+    }
+  }
+}
+
+// classroom -> lesson number -> who is there. More than one occupant means a double booking.
+pub type Grid = BTreeMap<String, BTreeMap<u8, Vec<Occupant>>>;
+
+pub fn grid(day: &DefaultDay, parity: WeekParity) -> Grid {
+  let mut grid = Grid::new();
+  for group in day.groups.iter() {
+    for lesson in group.lessons.iter().filter(|l| parity.includes(l)) {
+      let Some(classroom) = lesson.classroom.as_ref().map(|c| c.trim()).filter(|c| !c.is_empty()) else {
+        continue;
+      };
+      grid
+        .entry(classroom.to_string())
+        .or_default()
+        .entry(lesson.num)
+        .or_default()
+        .push(Occupant { group: group.name.clone(), subgroup: lesson.subgroup });
+    }
+  }
+  grid
+}
+
+// Every classroom that appears anywhere in the week, plus the reference list
+pub fn classrooms(days: &[DefaultDay], known: &[String]) -> Vec<String> {
+  let mut classrooms = days
+    .iter()
+    .flat_map(|d| d.groups.iter())
+    .flat_map(|g| g.lessons.iter())
+    .filter_map(|l| l.classroom.as_ref().map(|c| c.trim().to_string()))
+    .chain(known.iter().map(|c| c.trim().to_string()))
+    .filter(|c| !c.is_empty())
+    .collect::<BTreeSet<_>>()
+    .into_iter()
+    .collect::<Vec<_>>();
+  classrooms.sort_by(|a, b| natural_cmp(a, b));
+  classrooms
+}
+
+pub fn free_rooms(days: &[DefaultDay], known: &[String], day: Weekday, num: u8, parity: WeekParity) -> Vec<String> {
+  let grid = days
+    .iter()
+    .find(|d| d.day == day)
+    .map(|d| grid(d, parity))
+    .unwrap_or_default();
+  classrooms(days, known)
+    .into_iter()
+    .filter(|c| grid.get(c).is_none_or(|lessons| !lessons.contains_key(&num)))
+    .collect()
+}
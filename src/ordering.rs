@@ -0,0 +1,129 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use crate::i18n::{tr, Key};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortOrder {
+  #[default]
+  Natural,
+  Alphabetical,
+}
+
+impl SortOrder {
+  pub const ALL: [SortOrder; 2] = [SortOrder::Natural, SortOrder::Alphabetical];
+
+  pub fn compare(self, a: &str, b: &str) -> Ordering {
+    match self {
+      SortOrder::Natural => natural_cmp(a, b),
+      SortOrder::Alphabetical => a.cmp(b),
+    }
+  }
+}
+
+impl Display for SortOrder {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        SortOrder::Natural => tr(Key::SortNatural),
+        SortOrder::Alphabetical => tr(Key::SortAlphabetical),
+      }
+    )
+  }
+}
+
+// "ИС-21" < "ИС-111": runs of digits are compared as numbers
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+  let (mut a, mut b) = (chunks(a), chunks(b));
+  loop {
+    match (a.next(), b.next()) {
+      (None, None) => return Ordering::Equal,
+      (None, Some(_)) => return Ordering::Less,
+      (Some(_), None) => return Ordering::Greater,
+      (Some(x), Some(y)) => {
+        let ordering = match (is_number(x), is_number(y)) {
+          (true, true) => {
+            let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+            x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+          }
+          _ => x.to_lowercase().cmp(&y.to_lowercase()),
+        };
+        if ordering != Ordering::Equal {
+          return ordering;
+        }
+      }
+    }
+  }
+}
+
+pub fn shift<T>(items: &mut [T], idx: usize, up: bool) -> Option<usize> {
+  let target = match up {
+    true => idx.checked_sub(1)?,
+    false => idx + 1,
+  };
+  if target >= items.len() || idx >= items.len() {
+    return None;
+  }
+  items.swap(idx, target);
+  Some(target)
+}
+
+fn is_number(chunk: &str) -> bool {
+  chunk.starts_with(|c: char| c.is_ascii_digit())
+}
+
+fn chunks(value: &str) -> impl Iterator<Item = &str> {
+  let mut rest = value;
+  std::iter::from_fn(move || {
+    let first = rest.chars().next()?;
+    let digits = first.is_ascii_digit();
+    let end = rest
+      .find(|c: char| c.is_ascii_digit() != digits)
+      .unwrap_or(rest.len());
+    let (chunk, tail) = rest.split_at(end);
+    rest = tail;
+    Some(chunk)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn compares_digit_runs_as_numbers() {
+    assert_eq!(natural_cmp("ИС-2", "ИС-10"), Ordering::Less);
+    assert_eq!(natural_cmp("ИС-21", "ИС-111"), Ordering::Less);
+    assert_eq!(natural_cmp("ИС-02", "ИС-2"), Ordering::Equal);
+    assert_eq!(natural_cmp("Ир1-21", "Ир2-20"), Ordering::Less);
+  }
+
+  #[test]
+  fn ignores_case() {
+    assert_eq!(natural_cmp("ис-2", "ИС-2"), Ordering::Equal);
+    assert_eq!(natural_cmp("ис-3", "ИС-10"), Ordering::Less);
+    assert_eq!(natural_cmp("Базы данных", "алгебра"), Ordering::Greater);
+  }
+
+  #[test]
+  fn shorter_of_equal_prefixes_goes_first() {
+    assert_eq!(natural_cmp("ИС", "ИС-1"), Ordering::Less);
+    assert_eq!(natural_cmp("ИС-2а", "ИС-2"), Ordering::Greater);
+    assert_eq!(natural_cmp("", ""), Ordering::Equal);
+  }
+
+  #[test]
+  fn shifts_within_bounds() {
+    let mut items = [1, 2, 3];
+    assert_eq!(shift(&mut items, 0, true), None);
+    assert_eq!(shift(&mut items, 2, false), None);
+    assert_eq!(shift(&mut items, 3, true), None);
+    assert_eq!(items, [1, 2, 3]);
+
+    assert_eq!(shift(&mut items, 0, false), Some(1));
+    assert_eq!(items, [2, 1, 3]);
+    assert_eq!(shift(&mut items, 2, true), Some(1));
+    assert_eq!(items, [2, 3, 1]);
+  }
+}
@@ -0,0 +1,159 @@
+use std::{collections::BTreeMap, fmt::Display, fs, path::PathBuf};
+
+use chrono::Weekday;
+use maiq_shared::default::DefaultDay;
+
+use crate::{
+  env,
+  i18n::{tr, Key},
+  view::toolbar::map_weekday_to_str,
+};
+
+// One lesson of a teacher, seen from the teacher's side
+#[derive(Debug, Clone)]
+pub struct TeacherLesson {
+  pub day: Weekday,
+  pub num: u8,
+  pub subgroup: Option<u8>,
+  pub group: String,
+  pub subject: String,
+  pub classroom: Option<String>,
+  pub is_even: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+  Csv,
+  Html,
+}
+
+impl ExportFormat {
+  pub const ALL: [ExportFormat; 2] = [ExportFormat::Csv, ExportFormat::Html];
+
+  fn extension(self) -> &'static str {
+    match self {
+      ExportFormat::Csv => "csv",
+      ExportFormat::Html => "html",
+    }
+  }
+}
+
+impl Display for ExportFormat {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.extension().to_uppercase())
+  }
+}
+
+pub fn parity(is_even: Option<bool>) -> &'static str {
+  match is_even {
+    Some(true) => tr(Key::Even),
+    Some(false) => tr(Key::Odd),
+    None => "",
+  }
+}
+
+// Every teacher's week, ordered by day and lesson number
+pub fn teachers(days: &[DefaultDay]) -> BTreeMap<String, Vec<TeacherLesson>> {
+  let mut teachers = BTreeMap::<String, Vec<TeacherLesson>>::new();
+  for day in days {
+    for group in day.groups.iter() {
+      for lesson in group.lessons.iter() {
+        let Some(teacher) = lesson.teacher.as_ref().map(|t| t.trim()).filter(|t| !t.is_empty()) else {
+          continue;
+        };
+        teachers.entry(teacher.to_string()).or_default().push(TeacherLesson {
+          day: day.day,
+          num: lesson.num,
+          subgroup: lesson.subgroup,
+          group: group.name.clone(),
+          subject: lesson.name.clone(),
+          classroom: lesson.classroom.clone(),
+          is_even: lesson.is_even,
+        });
+      }
+    }
+  }
+
+  teachers
+    .values_mut()
+    .for_each(|lessons| lessons.sort_by_key(|l| (l.day.num_days_from_monday(), l.num, l.subgroup)));
+  teachers
+}
+
+fn columns(lesson: &TeacherLesson) -> [String; 6] {
+  [
+    map_weekday_to_str(lesson.day).to_string(),
+    match lesson.subgroup {
+      Some(subgroup) => format!("{}/{}", lesson.num, subgroup),
+      None => lesson.num.to_string(),
+    },
+    lesson.group.clone(),
+    lesson.subject.clone(),
+    lesson.classroom.clone().unwrap_or_default(),
+    parity(lesson.is_even).to_string(),
+  ]
+}
+
+fn header() -> [&'static str; 6] {
+  [tr(Key::Day), tr(Key::Num), tr(Key::Group), tr(Key::Subject), tr(Key::ClassroomShort), tr(Key::Parity)]
+}
+
+pub fn csv_escape(value: &str) -> String {
+  match value.contains([',', '"', '\n']) {
+    true => format!("\"{}\"", value.replace('"', "\"\"")),
+    false => value.to_string(),
+  }
+}
+
+pub fn to_csv(lessons: &[TeacherLesson]) -> String {
+  let mut out = header().join(",") + "\n";
+  for lesson in lessons {
+    out += &columns(lesson)
+      .iter()
+      .map(|c| csv_escape(c))
+      .collect::<Vec<_>>()
+      .join(",");
+    out += "\n";
+  }
+  out
+}
+
+pub fn to_html(teacher: &str, lessons: &[TeacherLesson]) -> String {
+  fn escape(value: &str) -> String {
+    value
+      .replace('&', "&amp;")
+      .replace('<', "&lt;")
+      .replace('>', "&gt;")
+      .replace('"', "&quot;")
+  }
+  let row = |cells: Vec<String>, tag: &str| {
+    let cells = cells
+      .iter()
+      .map(|c| format!("<{tag}>{}</{tag}>", escape(c)))
+      .collect::<String>();
+    format!("    <tr>{}</tr>\n", cells)
+  };
+
+  let mut out = format!(
+    "<!DOCTYPE html>\n<html>\n<head>\n  <meta charset=\"utf-8\">\n  <title>{0}</title>\n</head>\n<body>\n  <h1>{0}</h1>\n  <table border=\"1\" cellspacing=\"0\" cellpadding=\"4\">\n",
+    escape(teacher)
+  );
+  out += &row(header().map(String::from).to_vec(), "th");
+  for lesson in lessons {
+    out += &row(columns(lesson).to_vec(), "td");
+  }
+  out + "  </table>\n</body>\n</html>\n"
+}
+
+pub fn export(teacher: &str, lessons: &[TeacherLesson], format: ExportFormat) -> anyhow::Result<String> {
+  let dir = PathBuf::from(env::export_dir()).join("teachers");
+  fs::create_dir_all(&dir)?;
+  let name = teacher.replace(|c: char| !c.is_alphanumeric() && c != '-', "_");
+  let path = dir.join(format!("{}.{}", name.trim_matches('_'), format.extension()));
+  let content = match format {
+    ExportFormat::Csv => to_csv(lessons),
+    ExportFormat::Html => to_html(teacher, lessons),
+  };
+  fs::write(&path, content)?;
+  Ok(path.display().to_string())
+}
@@ -0,0 +1,55 @@
+use std::{fs, path::PathBuf};
+
+use chrono::{DateTime, Local};
+use maiq_shared::default::DefaultDay;
+use serde::{Deserialize, Serialize};
+
+use crate::{format, settings};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recovery {
+  pub saved_at: DateTime<Local>,
+  // The day file the editor was loaded from, if any
+  pub source: Option<String>,
+  pub snapshot: DefaultDay,
+}
+
+impl Recovery {
+  pub fn new(snapshot: &DefaultDay, source: Option<&str>) -> Self {
+    Self { saved_at: Local::now(), source: source.map(String::from), snapshot: snapshot.clone() }
+  }
+
+  pub fn load() -> Option<Self> {
+    let content = fs::read_to_string(path()).ok()?;
+    serde_json::from_str(&content)
+      .map_err(|err| eprintln!("warn -> can't parse recovery file: {}", err))
+      .ok()
+  }
+
+  pub fn save(&self) -> anyhow::Result<()> {
+    let path = path();
+    if let Some(dir) = path.parent() {
+      fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(self)?)?;
+    Ok(())
+  }
+
+  // What the snapshot was before the unsaved edits: the source file, or an empty day for a new one
+  pub fn original(&self) -> DefaultDay {
+    self
+      .source
+      .as_ref()
+      .and_then(|path| fs::read_to_string(path).ok())
+      .and_then(|content| format::from_str(&content).ok())
+      .unwrap_or_else(|| DefaultDay { day: self.snapshot.day, groups: vec![] })
+  }
+}
+
+pub fn path() -> PathBuf {
+  settings::config_path().with_file_name("recovery.json")
+}
+
+pub fn clear() {
+  _ = fs::remove_file(path());
+}
@@ -0,0 +1,169 @@
+use std::{collections::BTreeMap, fmt::Display, fs};
+
+use maiq_shared::default::DefaultDay;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  dictionary::Field,
+  env,
+  i18n::{tr, Key},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Kind {
+  #[default]
+  Teacher,
+  Subject,
+  Classroom,
+  Group,
+}
+
+impl Kind {
+  pub const ALL: [Kind; 4] = [Kind::Teacher, Kind::Subject, Kind::Classroom, Kind::Group];
+
+  pub fn field(self) -> Option<Field> {
+    match self {
+      Kind::Teacher => Some(Field::Teacher),
+      Kind::Subject => Some(Field::Subject),
+      Kind::Classroom => Some(Field::Classroom),
+      Kind::Group => None,
+    }
+  }
+}
+
+impl Display for Kind {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        Kind::Teacher => tr(Key::Teachers),
+        Kind::Subject => tr(Key::Subjects),
+        Kind::Classroom => tr(Key::Classrooms),
+        Kind::Group => tr(Key::Groups),
+      }
+    )
+  }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct References {
+  #[serde(default)]
+  pub teachers: Vec<String>,
+  #[serde(default)]
+  pub subjects: Vec<String>,
+  #[serde(default)]
+  pub classrooms: Vec<String>,
+  #[serde(default)]
+  pub groups: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+  pub kind: Kind,
+  pub value: String,
+  pub occurrences: usize,
+}
+
+impl References {
+  pub fn load() -> Self {
+    let path = env::references_path();
+    match fs::read_to_string(&path) {
+      Ok(content) => serde_json::from_str(&content).unwrap_or_else(|err| {
+        eprintln!("warn -> can't parse references from {}: {}", path, err);
+        Self::default()
+      }),
+      Err(_) => Self::default(),
+    }
+  }
+
+  pub fn save(&self) -> anyhow::Result<String> {
+    let path = env::references_path();
+    fs::write(&path, serde_json::to_string_pretty(self)?)?;
+    Ok(path)
+  }
+
+  pub fn entries(&self, kind: Kind) -> &Vec<String> {
+    match kind {
+      Kind::Teacher => &self.teachers,
+      Kind::Subject => &self.subjects,
+      Kind::Classroom => &self.classrooms,
+      Kind::Group => &self.groups,
+    }
+  }
+
+  pub fn entries_mut(&mut self, kind: Kind) -> &mut Vec<String> {
+    match kind {
+      Kind::Teacher => &mut self.teachers,
+      Kind::Subject => &mut self.subjects,
+      Kind::Classroom => &mut self.classrooms,
+      Kind::Group => &mut self.groups,
+    }
+  }
+
+  pub fn add(&mut self, kind: Kind, value: &str) -> bool {
+    let value = value.trim();
+    let entries = self.entries_mut(kind);
+    if value.is_empty() || entries.iter().any(|e| e == value) {
+      return false;
+    }
+    entries.push(value.to_string());
+    entries.sort();
+    true
+  }
+
+  pub fn mismatches(&self, days: &[DefaultDay]) -> Vec<Mismatch> {
+    let mut found = BTreeMap::<(Kind, String), usize>::new();
+    for kind in Kind::ALL {
+      let entries = self.entries(kind);
+      days
+        .iter()
+        .flat_map(|day| values(kind, day))
+        .filter(|value| !entries.iter().any(|e| e == value))
+        .for_each(|value| *found.entry((kind, value.to_string())).or_default() += 1);
+    }
+
+    found
+      .into_iter()
+      .map(|((kind, value), occurrences)| Mismatch { kind, value, occurrences })
+      .collect()
+  }
+}
+
+pub fn values(kind: Kind, day: &DefaultDay) -> Vec<&str> {
+  match kind.field() {
+    Some(field) => day
+      .groups
+      .iter()
+      .flat_map(|g| g.lessons.iter())
+      .map(|l| field.get(l).trim())
+      .filter(|v| !v.is_empty())
+      .collect(),
+    None => day
+      .groups
+      .iter()
+      .map(|g| g.name.trim())
+      .filter(|v| !v.is_empty())
+      .collect(),
+  }
+}
+
+pub fn rename(kind: Kind, from: &str, to: &str, day: &mut DefaultDay) -> usize {
+  let mut renamed = 0;
+  match kind.field() {
+    Some(field) => day
+      .groups
+      .iter_mut()
+      .flat_map(|g| g.lessons.iter_mut())
+      .filter(|l| field.get(l).trim() == from)
+      .for_each(|l| {
+        field.set(l, to.to_string());
+        renamed += 1;
+      }),
+    None => day.groups.iter_mut().filter(|g| g.name.trim() == from).for_each(|g| {
+      g.name = to.to_string();
+      renamed += 1;
+    }),
+  }
+  renamed
+}
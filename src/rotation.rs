@@ -0,0 +1,210 @@
+use std::{collections::BTreeSet, fmt::Display, fs};
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use maiq_shared::default::DefaultLesson;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  env,
+  i18n::{tr, trf, Key},
+};
+
+// Patterns that `DefaultLesson::is_even` can't express. They're kept in a client-side
+// `rotations.json`; the day file gets `WeekPattern::fallback` instead.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WeekPattern {
+  Cycle { length: u8, weeks: Vec<u8> },
+  MonthWeeks { weeks: Vec<u8> },
+}
+
+impl Default for WeekPattern {
+  fn default() -> Self {
+    WeekPattern::Cycle { length: 4, weeks: vec![1, 3] }
+  }
+}
+
+impl WeekPattern {
+  pub fn parse(input: &str) -> Result<Self, String> {
+    let (weeks, cycle) = input.split_once('/').ok_or(tr(Key::PatternFormat))?;
+    let weeks = weeks
+      .split(',')
+      .map(|w| w.trim().parse::<u8>().map_err(|_| trf(Key::NotAWeek, &[&w.trim()])))
+      .collect::<Result<Vec<_>, _>>()?;
+
+    let pattern = match cycle.trim() {
+      "м" | "m" => WeekPattern::MonthWeeks { weeks },
+      length => {
+        let length = length.parse().map_err(|_| trf(Key::NotACycle, &[&length]))?;
+        WeekPattern::Cycle { length, weeks }
+      }
+    };
+    pattern.validate()?;
+    Ok(pattern)
+  }
+
+  fn validate(&self) -> Result<(), String> {
+    let (weeks, max) = match self {
+      WeekPattern::Cycle { length, .. } if *length < 2 => return Err(tr(Key::CycleTooShort).into()),
+      WeekPattern::Cycle { length, weeks } => (weeks, *length),
+      WeekPattern::MonthWeeks { weeks } => (weeks, 5),
+    };
+    match weeks.iter().find(|w| **w == 0 || **w > max) {
+      Some(w) => Err(trf(Key::WeekOutOfRange, &[w, &max])),
+      None => Ok(()),
+    }
+  }
+
+  pub fn occurs(&self, week: i64, date: NaiveDate) -> bool {
+    match self {
+      WeekPattern::Cycle { length, weeks } => weeks.contains(&((week - 1).rem_euclid(*length as i64) as u8 + 1)),
+      WeekPattern::MonthWeeks { weeks } => weeks.contains(&((date.day0() / 7 + 1) as u8)),
+    }
+  }
+
+  // Whether the fallback takes place in exactly the pattern's weeks, e.g. 2,4/4 is every even week but 2/4 isn't
+  pub fn is_exact(&self) -> bool {
+    match self {
+      WeekPattern::Cycle { length, weeks } => {
+        let fallback = self.fallback();
+        let expected = (1..=*length)
+          .filter(|w| fallback.is_none_or(|even| (w % 2 == 0) == even))
+          .collect::<BTreeSet<_>>();
+        expected == weeks.iter().copied().collect()
+      }
+      WeekPattern::MonthWeeks { .. } => false,
+    }
+  }
+
+  // Cycle weeks are numbered from 1, so week 1 of an even-length cycle is an odd week
+  pub fn fallback(&self) -> Option<bool> {
+    match self {
+      WeekPattern::Cycle { length, weeks } if length % 2 == 0 => {
+        if weeks.iter().all(|w| w % 2 == 0) {
+          Some(true)
+        } else if weeks.iter().all(|w| w % 2 == 1) {
+          Some(false)
+        } else {
+          None
+        }
+      }
+      _ => None,
+    }
+  }
+}
+
+impl Display for WeekPattern {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let join = |weeks: &[u8]| weeks.iter().map(|w| w.to_string()).collect::<Vec<_>>().join(",");
+    match self {
+      WeekPattern::Cycle { length, weeks } => write!(f, "{}/{}", join(weeks), length),
+      WeekPattern::MonthWeeks { weeks } => write!(f, "{}/{}", join(weeks), tr(Key::MonthCycle)),
+    }
+  }
+}
+
+// Week 1 is the one containing the parity anchor
+pub fn week_number(date: NaiveDate, anchor: NaiveDate) -> i64 {
+  let monday = |d: NaiveDate| d - chrono::Duration::days(d.weekday().num_days_from_monday() as i64);
+  (monday(date) - monday(anchor)).num_weeks() + 1
+}
+
+pub fn is_even_week(week: i64) -> bool {
+  week.rem_euclid(2) == 0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rotation {
+  pub day: Weekday,
+  pub group: String,
+  pub num: u8,
+  pub subgroup: Option<u8>,
+  // An even and an odd lesson at the same number are different lessons
+  #[serde(default)]
+  pub is_even: Option<bool>,
+  pub pattern: WeekPattern,
+}
+
+// Group, number, subgroup and parity of the lesson a rotation belongs to
+pub type LessonKey<'a> = (&'a str, u8, Option<u8>, Option<bool>);
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Rotations(Vec<Rotation>);
+
+// Rotations describe the defaults, so they live next to them rather than next to the exports
+pub fn path() -> String {
+  format!("{}/rotations.json", env::defaults_dir())
+}
+
+impl Rotations {
+  pub fn load() -> Self {
+    let path = path();
+    match fs::read_to_string(&path) {
+      Ok(content) => serde_json::from_str(&content).unwrap_or_else(|err| {
+        eprintln!("warn -> can't parse rotations from {}: {}", path, err);
+        Self::default()
+      }),
+      Err(_) => Self::default(),
+    }
+  }
+
+  pub fn save(&self) -> anyhow::Result<String> {
+    let path = path();
+    fs::write(&path, serde_json::to_string_pretty(self)?)?;
+    Ok(path)
+  }
+
+  pub fn get(&self, day: Weekday, group: &str, lesson: &DefaultLesson) -> Option<&WeekPattern> {
+    self
+      .position(day, (group, lesson.num, lesson.subgroup, lesson.is_even))
+      .map(|idx| &self.0[idx].pattern)
+  }
+
+  pub fn for_day(&self, day: Weekday) -> impl Iterator<Item = &Rotation> {
+    self.0.iter().filter(move |r| r.day == day)
+  }
+
+  pub fn set(&mut self, day: Weekday, group: &str, lesson: &DefaultLesson, pattern: Option<WeekPattern>) {
+    let position = self.position(day, (group, lesson.num, lesson.subgroup, lesson.is_even));
+    match (position, pattern) {
+      (Some(idx), Some(pattern)) => self.0[idx].pattern = pattern,
+      (Some(idx), None) => _ = self.0.remove(idx),
+      (None, Some(pattern)) => self.0.push(Rotation {
+        day,
+        group: group.into(),
+        num: lesson.num,
+        subgroup: lesson.subgroup,
+        is_even: lesson.is_even,
+        pattern,
+      }),
+      (None, None) => (),
+    }
+  }
+
+  pub fn rekey(&mut self, day: Weekday, from: LessonKey, to: LessonKey) {
+    if let Some(idx) = self.position(day, from) {
+      let rotation = &mut self.0[idx];
+      (rotation.group, rotation.num, rotation.subgroup, rotation.is_even) = (to.0.into(), to.1, to.2, to.3);
+    }
+  }
+
+  pub fn rename_group(&mut self, day: Weekday, from: &str, to: &str) {
+    self
+      .0
+      .iter_mut()
+      .filter(|r| r.day == day && r.group == from)
+      .for_each(|r| r.group = to.into());
+  }
+
+  pub fn remove_group(&mut self, day: Weekday, group: &str) {
+    self.0.retain(|r| r.day != day || r.group != group);
+  }
+
+  fn position(&self, day: Weekday, (group, num, subgroup, is_even): LessonKey) -> Option<usize> {
+    self
+      .0
+      .iter()
+      .position(|r| r.day == day && r.group == group && r.num == num && r.subgroup == subgroup && r.is_even == is_even)
+  }
+}
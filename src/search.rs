@@ -0,0 +1,170 @@
+use std::fmt::Display;
+
+use chrono::Weekday;
+use maiq_shared::default::{DefaultDay, DefaultGroup, DefaultLesson};
+
+use crate::{
+  dictionary::Field,
+  i18n::{tr, Key},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scope {
+  #[default]
+  Day,
+  Week,
+}
+
+impl Scope {
+  pub const ALL: [Scope; 2] = [Scope::Day, Scope::Week];
+}
+
+impl Display for Scope {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        Scope::Day => tr(Key::ScopeDay),
+        Scope::Week => tr(Key::ScopeWeek),
+      }
+    )
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct Hit {
+  pub day: Weekday,
+  pub group: String,
+  pub num: Option<u8>,
+  pub before: String,
+  pub after: String,
+}
+
+pub fn matches_lesson(lesson: &DefaultLesson, query: &str) -> bool {
+  let query = query.trim().to_lowercase();
+  !query.is_empty()
+    && (lesson.num.to_string() == query
+      || Field::ALL
+        .iter()
+        .any(|f| f.get(lesson).to_lowercase().contains(&query)))
+}
+
+pub fn matches_group(group: &DefaultGroup, query: &str) -> bool {
+  query.trim().is_empty()
+    || group.name.to_lowercase().contains(&query.trim().to_lowercase())
+    || group.lessons.iter().any(|l| matches_lesson(l, query))
+}
+
+// Length in bytes of the `find` (already lowercase) at the start of `rest`, compared as `matches_lesson` does
+fn matched(rest: &str, find: &str) -> Option<usize> {
+  let mut find = find.chars();
+  for (idx, c) in rest.char_indices() {
+    for lower in c.to_lowercase() {
+      if find.next() != Some(lower) {
+        return None;
+      }
+    }
+    if find.as_str().is_empty() {
+      return Some(idx + c.len_utf8());
+    }
+  }
+  None
+}
+
+// Every occurrence of `find` whatever its case, or `None` if there's none
+fn replace_ignoring_case(value: &str, find: &str, replacement: &str) -> Option<String> {
+  let find = find.to_lowercase();
+  if find.is_empty() {
+    return None;
+  }
+
+  let (mut result, mut rest, mut found) = (String::new(), value, false);
+  while let Some(c) = rest.chars().next() {
+    match matched(rest, &find) {
+      Some(len) => {
+        result.push_str(replacement);
+        rest = &rest[len..];
+        found = true;
+      }
+      None => {
+        result.push(c);
+        rest = &rest[c.len_utf8()..];
+      }
+    }
+  }
+  found.then_some(result)
+}
+
+pub fn preview(day: &DefaultDay, find: &str, replacement: &str) -> Vec<Hit> {
+  let mut hits = vec![];
+  if find.is_empty() {
+    return hits;
+  }
+
+  for group in day.groups.iter() {
+    if let Some(after) = replace_ignoring_case(&group.name, find, replacement) {
+      hits.push(Hit { day: day.day, group: group.name.clone(), num: None, before: group.name.clone(), after });
+    }
+    for lesson in group.lessons.iter() {
+      for field in Field::ALL {
+        let before = field.get(lesson);
+        if let Some(after) = replace_ignoring_case(before, find, replacement) {
+          hits.push(Hit { day: day.day, group: group.name.clone(), num: Some(lesson.num), before: before.into(), after });
+        }
+      }
+    }
+  }
+
+  hits
+}
+
+// Group names `replace` changes, before and after
+pub fn renamed_groups(day: &DefaultDay, find: &str, replacement: &str) -> Vec<(String, String)> {
+  day
+    .groups
+    .iter()
+    .filter_map(|g| Some((g.name.clone(), replace_ignoring_case(&g.name, find, replacement)?)))
+    .collect()
+}
+
+pub fn replace(day: &mut DefaultDay, find: &str, replacement: &str) -> usize {
+  let mut replaced = 0;
+  if find.is_empty() {
+    return replaced;
+  }
+
+  for group in day.groups.iter_mut() {
+    if let Some(name) = replace_ignoring_case(&group.name, find, replacement) {
+      group.name = name;
+      replaced += 1;
+    }
+    for lesson in group.lessons.iter_mut() {
+      for field in Field::ALL {
+        if let Some(value) = replace_ignoring_case(field.get(lesson), find, replacement) {
+          field.set(lesson, value);
+          replaced += 1;
+        }
+      }
+    }
+  }
+
+  replaced
+}
+
+#[cfg(test)]
+mod tests {
+  use super::replace_ignoring_case;
+
+  #[test]
+  fn replaces_whatever_the_case() {
+    assert_eq!(replace_ignoring_case("Иванова А.А.", "иванова", "Петрова").as_deref(), Some("Петрова А.А."));
+    assert_eq!(replace_ignoring_case("ауд. 204, Ауд. 205", "АУД.", "каб.").as_deref(), Some("каб. 204, каб. 205"));
+  }
+
+  #[test]
+  fn leaves_values_without_a_match() {
+    assert_eq!(replace_ignoring_case("Математика", "физика", "химия"), None);
+    assert_eq!(replace_ignoring_case("Математика", "", "химия"), None);
+  }
+}
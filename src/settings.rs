@@ -0,0 +1,210 @@
+use std::{fmt::Display, fs, path::PathBuf, sync::RwLock};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  env,
+  i18n::{tr, trf, Key},
+};
+
+static CURRENT: RwLock<Option<Settings>> = RwLock::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AppTheme {
+  #[default]
+  Light,
+  Dark,
+  System,
+}
+
+impl AppTheme {
+  pub const ALL: [AppTheme; 3] = [AppTheme::Light, AppTheme::Dark, AppTheme::System];
+}
+
+impl Display for AppTheme {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        AppTheme::Light => tr(Key::ThemeLight),
+        AppTheme::Dark => tr(Key::ThemeDark),
+        AppTheme::System => tr(Key::ThemeSystem),
+      }
+    )
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+  #[default]
+  Russian,
+  English,
+}
+
+impl Language {
+  pub const ALL: [Language; 2] = [Language::Russian, Language::English];
+}
+
+impl Display for Language {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        Language::Russian => "Русский",
+        Language::English => "English",
+      }
+    )
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SettingsField {
+  ExportDir,
+  DefaultsDir,
+  ServerUrl,
+  ParityAnchor,
+  SemesterStart,
+  SemesterEnd,
+  MaxLessonNum,
+  MaxSubgroups,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+  pub export_dir: String,
+  pub defaults_dir: String,
+  pub server_url: String,
+  pub theme: AppTheme,
+  pub language: Language,
+  pub parity_anchor: NaiveDate,
+  pub semester_start: NaiveDate,
+  pub semester_end: NaiveDate,
+  pub max_lesson_num: u8,
+  pub max_subgroups: u8,
+}
+
+impl Default for Settings {
+  fn default() -> Self {
+    Self {
+      export_dir: "export".into(),
+      defaults_dir: "default".into(),
+      server_url: String::new(),
+      theme: AppTheme::default(),
+      language: Language::default(),
+      parity_anchor: NaiveDate::from_ymd_opt(2023, 9, 4).unwrap(),
+      semester_start: NaiveDate::from_ymd_opt(2023, 9, 1).unwrap(),
+      semester_end: NaiveDate::from_ymd_opt(2023, 12, 30).unwrap(),
+      max_lesson_num: 10,
+      max_subgroups: 2,
+    }
+  }
+}
+
+macro_rules! overrides {
+  [$($var_name: ident => $field: ident),*] => {
+    const OVERRIDES: &[&str] = &[$(stringify!($var_name)),*];
+
+    fn apply_overrides(settings: &mut Settings) {
+      $(if let Some(value) = env::parse_var(stringify!($var_name)) {
+        settings.$field = value;
+      })*
+    }
+  };
+}
+
+overrides![
+  EXPORT_DIRECTORY => export_dir,
+  DEFAULTS_DIRECTORY => defaults_dir,
+  SERVER_URL => server_url,
+  PARITY_ANCHOR => parity_anchor,
+  SEMESTER_START => semester_start,
+  SEMESTER_END => semester_end,
+  MAX_LESSON_NUM => max_lesson_num,
+  MAX_SUBGROUPS => max_subgroups
+];
+
+impl Settings {
+  // What's in effect: the settings file with the environment's overrides on top
+  pub fn load() -> Self {
+    Self::from_file().with_overrides()
+  }
+
+  // Only what's saved, so the environment's values don't end up written to the file
+  pub fn from_file() -> Self {
+    let path = config_path();
+    match fs::read_to_string(&path) {
+      Ok(content) => serde_json::from_str(&content).unwrap_or_else(|err| {
+        eprintln!("warn -> can't parse settings from {}: {}", path.display(), err);
+        Self::default()
+      }),
+      Err(_) => Self::default(),
+    }
+  }
+
+  pub fn with_overrides(mut self) -> Self {
+    apply_overrides(&mut self);
+    self
+  }
+
+  pub fn save(&self) -> anyhow::Result<String> {
+    let path = config_path();
+    if let Some(dir) = path.parent() {
+      fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(self)?)?;
+    Ok(path.display().to_string())
+  }
+
+  pub fn validate(&self) -> Vec<(SettingsField, String)> {
+    let mut errors = vec![];
+    if self.export_dir.trim().is_empty() {
+      errors.push((SettingsField::ExportDir, tr(Key::ExportDirRequired).into()));
+    }
+    if !PathBuf::from(&self.defaults_dir).is_dir() {
+      errors.push((SettingsField::DefaultsDir, trf(Key::DirNotFound, &[&self.defaults_dir])));
+    }
+    if !self.server_url.is_empty() && !self.server_url.starts_with("http://") && !self.server_url.starts_with("https://") {
+      errors.push((SettingsField::ServerUrl, tr(Key::UrlScheme).into()));
+    }
+    if self.semester_end < self.semester_start {
+      errors.push((SettingsField::SemesterEnd, tr(Key::SemesterEndsBeforeStart).into()));
+    }
+    if !(1..=20).contains(&self.max_lesson_num) {
+      errors.push((SettingsField::MaxLessonNum, trf(Key::OneTo, &[&20])));
+    }
+    if !(1..=9).contains(&self.max_subgroups) {
+      errors.push((SettingsField::MaxSubgroups, trf(Key::OneTo, &[&9])));
+    }
+    errors
+  }
+}
+
+pub fn config_path() -> PathBuf {
+  dirs::config_dir()
+    .map(|dir| dir.join("maiq-client"))
+    .unwrap_or_default()
+    .join("settings.json")
+}
+
+pub fn overridden() -> Vec<&'static str> {
+  OVERRIDES
+    .iter()
+    .filter(|var| env::var(**var).is_some())
+    .copied()
+    .collect()
+}
+
+pub fn get<T>(f: impl FnOnce(&Settings) -> T) -> T {
+  match CURRENT.read().unwrap().as_ref() {
+    Some(settings) => f(settings),
+    None => f(&Settings::default()),
+  }
+}
+
+pub fn apply(settings: Settings) {
+  *CURRENT.write().unwrap() = Some(settings);
+}
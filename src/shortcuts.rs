@@ -0,0 +1,53 @@
+use iced::{
+  event::Status,
+  keyboard::{self, KeyCode, Modifiers},
+  subscription, Event, Subscription,
+};
+
+use crate::i18n::Key;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shortcut {
+  Export,
+  Import,
+  NewGroup,
+  NewLesson,
+  RemoveEditedLesson,
+  FocusNext,
+  FocusPrevious,
+  Help,
+}
+
+pub const CHEAT_SHEET: [(&str, Key); 8] = [
+  ("Ctrl+S", Key::Export),
+  ("Ctrl+O", Key::ShortcutImport),
+  ("Ctrl+N", Key::ShortcutNewGroup),
+  ("Ctrl+Enter", Key::ShortcutNewLesson),
+  ("Del", Key::ShortcutRemoveEditedLesson),
+  ("Tab", Key::ShortcutFocusNext),
+  ("Shift+Tab", Key::ShortcutFocusPrevious),
+  ("F1", Key::ShortcutHelp),
+];
+
+pub fn subscription() -> Subscription<Shortcut> {
+  subscription::events_with(|event, status| match event {
+    Event::Keyboard(keyboard::Event::KeyPressed { key_code, modifiers }) => resolve(key_code, modifiers, status),
+    _ => None,
+  })
+}
+
+fn resolve(key_code: KeyCode, modifiers: Modifiers, status: Status) -> Option<Shortcut> {
+  let shortcut = match (key_code, modifiers.control()) {
+    (KeyCode::S, true) => Shortcut::Export,
+    (KeyCode::O, true) => Shortcut::Import,
+    (KeyCode::N, true) => Shortcut::NewGroup,
+    (KeyCode::Enter, true) => Shortcut::NewLesson,
+    (KeyCode::Tab, false) if modifiers.shift() => Shortcut::FocusPrevious,
+    (KeyCode::Tab, false) => Shortcut::FocusNext,
+    (KeyCode::F1, _) => Shortcut::Help,
+    // focused text input uses Delete to erase characters, so this only fires with nothing focused
+    (KeyCode::Delete, false) if status == Status::Ignored => Shortcut::RemoveEditedLesson,
+    _ => return None,
+  };
+  Some(shortcut)
+}
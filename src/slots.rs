@@ -0,0 +1,121 @@
+use std::collections::BTreeSet;
+
+use chrono::Weekday;
+use maiq_shared::default::{DefaultDay, DefaultLesson};
+
+use crate::{
+  env, gaps,
+  occupancy::{self, overlaps, WeekParity},
+};
+
+// A place the lesson could move to: the group, the teacher and the classroom are all free there
+#[derive(Debug, Clone)]
+pub struct Slot {
+  pub day: Weekday,
+  pub num: u8,
+  pub is_even: Option<bool>,
+  pub classroom: Option<String>,
+  // Gaps added to the group's and the teacher's day; negative if the move closes some
+  pub new_gaps: i32,
+}
+
+fn weeks(is_even: Option<bool>) -> Vec<WeekParity> {
+  match is_even {
+    None => WeekParity::ALL.to_vec(),
+    Some(true) => vec![WeekParity::Even],
+    Some(false) => vec![WeekParity::Odd],
+  }
+}
+
+// `days` must not contain the lesson being moved, otherwise it blocks its own neighbourhood.
+// Sorted by the fewest new gaps, then by day and number.
+pub fn find(days: &[DefaultDay], group: &str, lesson: &DefaultLesson, classrooms: &[String]) -> Vec<Slot> {
+  let teacher = lesson.teacher.as_deref().map(str::trim).filter(|t| !t.is_empty());
+  let parities = match lesson.is_even {
+    None => vec![None],
+    Some(_) => vec![Some(false), Some(true)],
+  };
+
+  let mut slots = vec![];
+  for day in days {
+    // the group's and the teacher's lessons of the day with their parity
+    let own = |filter: &dyn Fn(&str, &DefaultLesson) -> bool| {
+      day
+        .groups
+        .iter()
+        .flat_map(|g| g.lessons.iter().map(move |l| (g.name.as_str(), l)))
+        .filter(|(g, l)| filter(g, l))
+        .map(|(_, l)| (l.num, l.is_even))
+        .collect::<Vec<_>>()
+    };
+    let group_lessons =
+      own(&|g, l| g == group && (l.subgroup.is_none() || lesson.subgroup.is_none() || l.subgroup == lesson.subgroup));
+    let teacher_lessons = own(&|_, l| teacher.is_some() && l.teacher.as_deref().map(str::trim) == teacher);
+
+    for is_even in parities.iter().copied() {
+      for num in 1..=env::max_lesson_num() {
+        let busy = |lessons: &[(u8, Option<bool>)]| lessons.iter().any(|(n, p)| *n == num && overlaps(*p, is_even));
+        if busy(&group_lessons) || busy(&teacher_lessons) {
+          continue;
+        }
+
+        let Some(classroom) = free_classroom(day, num, is_even, lesson.classroom.as_deref(), classrooms) else {
+          continue;
+        };
+
+        let mut new_gaps = added_gaps(&group_lessons, num, is_even);
+        if teacher.is_some() {
+          new_gaps += added_gaps(&teacher_lessons, num, is_even);
+        }
+        slots.push(Slot { day: day.day, num, is_even, classroom, new_gaps });
+      }
+    }
+  }
+
+  slots.sort_by_key(|s| (s.new_gaps, s.day.num_days_from_monday(), s.num));
+  slots
+}
+
+fn added_gaps(lessons: &[(u8, Option<bool>)], num: u8, is_even: Option<bool>) -> i32 {
+  weeks(is_even)
+    .into_iter()
+    .map(|week| {
+      let mut nums = lessons
+        .iter()
+        .filter(|(_, p)| overlaps(*p, Some(week == WeekParity::Even)))
+        .map(|(n, _)| *n)
+        .collect::<BTreeSet<_>>();
+      let before = gaps::count(&nums) as i32;
+      nums.insert(num);
+      gaps::count(&nums) as i32 - before
+    })
+    .sum()
+}
+
+// The lesson keeps its classroom when it's free; otherwise any free one will do.
+// A lesson without a classroom doesn't need one, that's `Some(None)`.
+fn free_classroom(
+  day: &DefaultDay,
+  num: u8,
+  is_even: Option<bool>,
+  current: Option<&str>,
+  classrooms: &[String],
+) -> Option<Option<String>> {
+  let Some(current) = current.map(str::trim).filter(|c| !c.is_empty()) else {
+    return Some(None);
+  };
+
+  let taken = weeks(is_even)
+    .into_iter()
+    .flat_map(|week| {
+      occupancy::grid(day, week)
+        .into_iter()
+        .filter(|(_, lessons)| lessons.contains_key(&num))
+        .map(|(classroom, _)| classroom)
+    })
+    .collect::<BTreeSet<_>>();
+  std::iter::once(current)
+    .chain(classrooms.iter().map(String::as_str))
+    .find(|c| !taken.contains(*c))
+    .map(|c| Some(c.to_string()))
+}
@@ -0,0 +1,124 @@
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use chrono::Weekday;
+use maiq_shared::default::{DefaultDay, DefaultLesson};
+
+use crate::{
+  env,
+  i18n::{tr, Key},
+  pivot::csv_escape,
+  view::toolbar::map_weekday_to_str,
+};
+
+// Loads are in lessons per week: an even- or odd-week lesson counts as half
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+  pub teachers: Vec<(String, f32)>,
+  pub groups: Vec<(String, f32)>,
+  pub subjects: Vec<(String, f32)>,
+  // Percent of the week's lesson slots the classroom is taken
+  pub classrooms: Vec<(String, f32)>,
+  pub days: Vec<(Weekday, f32)>,
+}
+
+type Slot = (Weekday, u8, Option<bool>);
+
+// Lessons at the same time count once, so subgroups or a teacher's merged groups aren't counted twice
+fn load(slots: &[Slot]) -> f32 {
+  let mut times = BTreeMap::<(u8, u8), Vec<Option<bool>>>::new();
+  for (day, num, is_even) in slots {
+    times
+      .entry((day.num_days_from_monday() as u8, *num))
+      .or_default()
+      .push(*is_even);
+  }
+  times
+    .values_mut()
+    .map(|parities| match parities.contains(&None) {
+      true => 1.,
+      false => {
+        parities.sort();
+        parities.dedup();
+        parities.len() as f32 * 0.5
+      }
+    })
+    .sum()
+}
+
+// Lessons are summed per key; within one key's `scope` lessons at the same time count once
+fn by(days: &[DefaultDay], key: impl Fn(&str, &DefaultLesson) -> Option<(String, String)>) -> Vec<(String, f32)> {
+  let mut slots = BTreeMap::<(String, String), Vec<Slot>>::new();
+  for day in days {
+    for group in day.groups.iter() {
+      for lesson in group.lessons.iter() {
+        if let Some(key) = key(&group.name, lesson).filter(|(k, _)| !k.is_empty()) {
+          slots
+            .entry(key)
+            .or_default()
+            .push((day.day, lesson.num, lesson.is_even));
+        }
+      }
+    }
+  }
+
+  let mut loads = BTreeMap::<String, f32>::new();
+  for ((key, _), slots) in slots {
+    *loads.entry(key).or_default() += load(&slots);
+  }
+  let mut loads = loads.into_iter().collect::<Vec<_>>();
+  loads.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+  loads
+}
+
+pub fn compute(days: &[DefaultDay]) -> Stats {
+  let trimmed = |value: &Option<String>| value.as_deref().map(|v| v.trim().to_string());
+  let week_slots = (days.len() * env::max_lesson_num() as usize).max(1) as f32;
+
+  Stats {
+    teachers: by(days, |_, l| Some((trimmed(&l.teacher)?, String::new()))),
+    groups: by(days, |group, _| Some((group.to_string(), String::new()))),
+    // a subject is counted per group, so parallel lessons of different groups all add up
+    subjects: by(days, |group, l| Some((l.name.trim().to_string(), group.to_string()))),
+    classrooms: by(days, |_, l| Some((trimmed(&l.classroom)?, String::new())))
+      .into_iter()
+      .map(|(classroom, load)| (classroom, load / week_slots * 100.))
+      .collect(),
+    days: days
+      .iter()
+      .map(|day| {
+        let groups = by(std::slice::from_ref(day), |group, _| Some((group.to_string(), String::new())));
+        (day.day, groups.iter().map(|(_, load)| load).sum())
+      })
+      .collect(),
+  }
+}
+
+pub fn to_csv(stats: &Stats) -> String {
+  let mut out = format!("{},{},{}\n", tr(Key::Section), tr(Key::Name), tr(Key::Value));
+  let mut section = |name: &str, rows: Vec<(String, f32)>| {
+    for (key, value) in rows {
+      out += &format!("{},{},{:.1}\n", csv_escape(name), csv_escape(&key), value);
+    }
+  };
+  section(tr(Key::Teachers), stats.teachers.clone());
+  section(tr(Key::Groups), stats.groups.clone());
+  section(tr(Key::Subjects), stats.subjects.clone());
+  section(tr(Key::ClassroomUtilization), stats.classrooms.clone());
+  section(
+    tr(Key::DayLoad),
+    stats
+      .days
+      .iter()
+      .map(|(day, load)| (map_weekday_to_str(*day).to_string(), *load))
+      .collect(),
+  );
+  out
+}
+
+pub fn export(stats: &Stats) -> anyhow::Result<String> {
+  let dir = PathBuf::from(env::export_dir());
+  fs::create_dir_all(&dir)?;
+  let path = dir.join("statistics.csv");
+  fs::write(&path, to_csv(stats))?;
+  Ok(path.display().to_string())
+}
@@ -0,0 +1,74 @@
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  fmt::Display,
+};
+
+use chrono::Weekday;
+use maiq_shared::default::{DefaultDay, DefaultLesson};
+
+use crate::{
+  i18n::{trf, Key},
+  occupancy::overlaps,
+  ordering::natural_cmp,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+  pub teacher: String,
+  // Lessons the teacher already has that day, fewer is better
+  pub load: usize,
+}
+
+impl Display for Candidate {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", trf(Key::CandidateLoad, &[&self.teacher, &self.load]))
+  }
+}
+
+fn teacher(lesson: &DefaultLesson) -> Option<&str> {
+  lesson.teacher.as_deref().map(str::trim).filter(|t| !t.is_empty())
+}
+
+// Who teaches what, learned from the whole week
+pub fn subject_teachers(days: &[DefaultDay]) -> BTreeMap<String, BTreeSet<String>> {
+  let mut subjects = BTreeMap::<String, BTreeSet<String>>::new();
+  days
+    .iter()
+    .flat_map(|d| d.groups.iter())
+    .flat_map(|g| g.lessons.iter())
+    .for_each(|l| {
+      if let Some(teacher) = teacher(l) {
+        subjects
+          .entry(l.name.trim().to_lowercase())
+          .or_default()
+          .insert(teacher.to_string());
+      }
+    });
+  subjects
+}
+
+// Teachers of the same subject who are free at the lesson's number and parity, least loaded first
+pub fn candidates(days: &[DefaultDay], day: Weekday, lesson: &DefaultLesson) -> Vec<Candidate> {
+  let lessons = days
+    .iter()
+    .filter(|d| d.day == day)
+    .flat_map(|d| d.groups.iter())
+    .flat_map(|g| g.lessons.iter())
+    .collect::<Vec<_>>();
+  let absent = teacher(lesson);
+
+  let mut candidates = subject_teachers(days)
+    .remove(&lesson.name.trim().to_lowercase())
+    .unwrap_or_default()
+    .into_iter()
+    .filter(|t| Some(t.as_str()) != absent)
+    .filter(|t| {
+      !lessons
+        .iter()
+        .any(|l| teacher(l) == Some(t.as_str()) && l.num == lesson.num && overlaps(l.is_even, lesson.is_even))
+    })
+    .map(|t| Candidate { load: lessons.iter().filter(|l| teacher(l) == Some(t.as_str())).count(), teacher: t })
+    .collect::<Vec<_>>();
+  candidates.sort_by(|a, b| a.load.cmp(&b.load).then_with(|| natural_cmp(&a.teacher, &b.teacher)));
+  candidates
+}
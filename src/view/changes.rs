@@ -0,0 +1,283 @@
+use std::{collections::BTreeMap, future::Future};
+
+use chrono::{Datelike, Local, NaiveDate};
+use iced::{
+  widget::{column, container, row, scrollable, text},
+  Element, Length,
+};
+use iced_aw::{date_picker::Date, DatePicker, Icon};
+use maiq_shared::{
+  default::{DefaultGroup, DefaultLesson},
+  Snapshot,
+};
+
+use crate::{
+  changes,
+  i18n::{tr, trf, Key},
+  ordering,
+};
+
+use super::{
+  basic_button,
+  default_group::{GroupComponent, ViewOptions},
+  icon_button,
+  lesson::{InvalidInput, LessonComponent, ValidatedField},
+  toolbar::map_weekday_to_str,
+  with_icon, Component, GroupMessage, LessonMessage,
+};
+
+#[derive(Debug)]
+pub struct ChangesEditor {
+  date: NaiveDate,
+  groups: Vec<DefaultGroup>,
+  show_picker: bool,
+  uid: Option<String>,
+  // Edited since the date's snapshot was loaded or exported
+  dirty: bool,
+  // A date picked over unexported edits waits for a confirmation
+  pending: Option<NaiveDate>,
+  // Rejected numbers are shown as typed, with the error, the same as in the defaults editor
+  invalid: BTreeMap<(usize, usize, ValidatedField), InvalidInput>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+  OpenPicker,
+  CancelPicker,
+  PickDate(Date),
+  Discard,
+  KeepEditing,
+  CreateGroup,
+  Group((usize, GroupMessage)),
+  Export,
+}
+
+impl Default for ChangesEditor {
+  fn default() -> Self {
+    let mut editor = Self {
+      date: Local::now().date_naive(),
+      groups: vec![],
+      show_picker: false,
+      uid: None,
+      dirty: false,
+      pending: None,
+      invalid: BTreeMap::new(),
+    };
+    editor.set_date(editor.date);
+    editor
+  }
+}
+
+impl ChangesEditor {
+  pub fn snapshot(&self) -> Snapshot {
+    changes::snapshot(self.date, &self.groups)
+  }
+
+  // A previously exported snapshot for the date is loaded for further editing
+  pub fn set_date(&mut self, date: NaiveDate) {
+    let snapshot = changes::load(date);
+    self.date = date;
+    self.uid = snapshot.as_ref().map(|s| s.uid.clone());
+    self.groups = snapshot.as_ref().map(changes::groups).unwrap_or_default();
+    self.dirty = false;
+    self.pending = None;
+    self.invalid.clear();
+  }
+
+  pub fn date(&self) -> NaiveDate {
+    self.date
+  }
+
+  pub fn is_dirty(&self) -> bool {
+    self.dirty
+  }
+
+  // Imported groups replace whatever was being edited; they aren't exported until the user does so
+  pub fn load(&mut self, date: NaiveDate, groups: Vec<DefaultGroup>) {
+    self.date = date;
+    self.uid = None;
+    self.groups = groups;
+    self.dirty = true;
+    self.invalid.clear();
+  }
+
+  // Each lesson takes the place of the group's lesson with the same number and subgroup
+  pub fn put_lessons(&mut self, lessons: Vec<(String, DefaultLesson)>) {
+    self.dirty = true;
+    self.invalid.clear();
+    for (name, lesson) in lessons {
+      let idx = match self.groups.iter().position(|g| g.name == name) {
+        Some(idx) => idx,
+        None => {
+          self.groups.push(DefaultGroup { name, lessons: vec![] });
+          self.groups.len() - 1
+        }
+      };
+      let group = &mut self.groups[idx];
+      group
+        .lessons
+        .retain(|l| (l.num, l.subgroup) != (lesson.num, lesson.subgroup));
+      group.lessons.push(lesson);
+      group.lessons.sort_by_key(|l| (l.num, l.subgroup));
+    }
+  }
+
+  pub fn export(&mut self) -> impl Future<Output = Result<Option<String>, String>> {
+    let snapshot = self.snapshot();
+    self.uid = Some(snapshot.uid.clone());
+    self.dirty = false;
+    async move {
+      changes::save(&snapshot)
+        .map(|path| Some(trf(Key::ChangesExported, &[&changes::date(&snapshot).format("%d.%m.%Y"), &path])))
+        .map_err(|err| err.to_string())
+    }
+  }
+
+  fn invalid_in(&self, group: usize) -> Vec<(usize, InvalidInput)> {
+    self
+      .invalid
+      .iter()
+      .filter(|((g, ..), _)| *g == group)
+      .map(|((_, lesson, _), invalid)| (*lesson, invalid.clone()))
+      .collect()
+  }
+
+  fn edit_number(&mut self, group: usize, lesson: usize, field: ValidatedField, input: String) {
+    let Some(l) = self.groups.get_mut(group).and_then(|g| g.lessons.get_mut(lesson)) else {
+      return;
+    };
+    let result = match field {
+      ValidatedField::Subgroup => l.set_subgroup(&input),
+      _ => l.set_num(&input),
+    };
+    match result {
+      Ok(_) => _ = self.invalid.remove(&(group, lesson, field)),
+      Err(error) => {
+        _ = self
+          .invalid
+          .insert((group, lesson, field), InvalidInput { field, input, error })
+      }
+    }
+  }
+
+  fn update_group(&mut self, idx: usize, message: GroupMessage) {
+    let reorders = matches!(
+      &message,
+      GroupMessage::Remove
+        | GroupMessage::Duplicate
+        | GroupMessage::MoveUp
+        | GroupMessage::MoveDown
+        | GroupMessage::Lesson((_, LessonMessage::Remove | LessonMessage::MoveUp | LessonMessage::MoveDown))
+    );
+    if reorders {
+      self.invalid.clear();
+    }
+
+    match message {
+      GroupMessage::Lesson((lesson, LessonMessage::EditNum(input))) => self.edit_number(idx, lesson, ValidatedField::Num, input),
+      GroupMessage::Lesson((lesson, LessonMessage::EditSubgroup(input))) => {
+        self.edit_number(idx, lesson, ValidatedField::Subgroup, input)
+      }
+      GroupMessage::Remove => _ = self.groups.remove(idx),
+      GroupMessage::MoveUp => _ = ordering::shift(&mut self.groups, idx, true),
+      GroupMessage::MoveDown => _ = ordering::shift(&mut self.groups, idx, false),
+      GroupMessage::Duplicate => {
+        if let Some(g) = self.groups.get(idx) {
+          let copy = DefaultGroup { name: trf(Key::GroupCopy, &[&g.name]), ..g.clone() };
+          self.groups.insert(idx + 1, copy);
+        }
+      }
+      message => {
+        if let Some(group) = self.groups.get_mut(idx) {
+          group.update(message)
+        }
+      }
+    }
+  }
+}
+
+impl Component for ChangesEditor {
+  type Message = Message;
+
+  fn update(&mut self, message: Self::Message) {
+    match message {
+      Message::OpenPicker => self.show_picker = true,
+      Message::CancelPicker => self.show_picker = false,
+      Message::PickDate(date) => {
+        self.show_picker = false;
+        match NaiveDate::from_ymd_opt(date.year, date.month, date.day) {
+          Some(date) if self.dirty && date != self.date => self.pending = Some(date),
+          Some(date) => self.set_date(date),
+          None => (),
+        }
+      }
+      Message::Discard => {
+        if let Some(date) = self.pending.take() {
+          self.set_date(date);
+        }
+      }
+      Message::KeepEditing => self.pending = None,
+      Message::CreateGroup => {
+        self.dirty = true;
+        self.groups.push(DefaultGroup::default())
+      }
+      Message::Group((idx, message)) => {
+        self.dirty = true;
+        self.update_group(idx, message)
+      }
+      Message::Export => (),
+    }
+  }
+
+  fn view(&self) -> Element<Self::Message> {
+    let label = format!("{} ({})", self.date.format("%d.%m.%Y"), map_weekday_to_str(self.date.weekday()));
+    let picker = DatePicker::new(
+      self.show_picker,
+      self.date,
+      basic_button(with_icon(text(label), Icon::Calendar), Message::OpenPicker),
+      Message::CancelPicker,
+      Message::PickDate,
+    );
+
+    let mut header = row![
+      text(tr(Key::ChangesFor)),
+      picker,
+      icon_button(Icon::Plus).on_press(Message::CreateGroup),
+      basic_button(with_icon(tr(Key::Export), Icon::Upload), Message::Export),
+    ]
+    .align_items(iced::Alignment::Center)
+    .spacing(10);
+    if let Some(uid) = &self.uid {
+      header = header.push(text(format!("uid: {}", uid)).size(14));
+    }
+
+    let groups = column(
+      self
+        .groups
+        .iter()
+        .enumerate()
+        .map(|(idx, group)| {
+          group
+            .view_with(&ViewOptions { invalid: self.invalid_in(idx), parity: false, ..ViewOptions::default() })
+            .map(move |msg| Message::Group((idx, msg)))
+        })
+        .collect(),
+    );
+
+    let mut content = column![header].spacing(10).padding([10, 15]);
+    if let Some(date) = self.pending {
+      let question = trf(Key::ConfirmDiscardChanges, &[&self.date.format("%d.%m.%Y"), &date.format("%d.%m.%Y")]);
+      content = content.push(
+        row![
+          text(question).width(Length::Fill),
+          basic_button(with_icon(tr(Key::DiscardChanges), Icon::Trash), Message::Discard),
+          basic_button(with_icon(tr(Key::Cancel), Icon::X), Message::KeepEditing),
+        ]
+        .align_items(iced::Alignment::Center)
+        .spacing(10),
+      );
+    }
+    let content = content.push(scrollable(groups));
+    container(content).width(Length::Fill).into()
+  }
+}
@@ -0,0 +1,31 @@
+use iced::{
+  widget::{column, row, text},
+  Length,
+};
+use iced_aw::{Card, Icon};
+
+use crate::{
+  app::AppMessage,
+  i18n::{tr, Key},
+  shortcuts::{Shortcut, CHEAT_SHEET},
+};
+
+use super::with_icon;
+
+type Element<'a> = iced::Element<'a, AppMessage, iced::Renderer>;
+
+pub fn cheat_sheet<'a>() -> Element<'a> {
+  let rows = CHEAT_SHEET
+    .iter()
+    .map(|(keys, action)| {
+      row![text(*keys).width(Length::Fixed(100.)), text(tr(*action))]
+        .spacing(10)
+        .into()
+    })
+    .collect::<Vec<Element>>();
+
+  Card::new(with_icon(tr(Key::Shortcuts), Icon::Keyboard), column(rows).spacing(5))
+    .max_width(400.)
+    .on_close(AppMessage::Shortcut(Shortcut::Help))
+    .into()
+}
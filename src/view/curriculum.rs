@@ -0,0 +1,117 @@
+use iced::{
+  theme,
+  widget::{checkbox, column, container, row, scrollable, text, Rule},
+  Element, Length,
+};
+use iced_aw::Icon;
+
+use crate::{
+  curriculum::{self, Row, Status},
+  i18n::{tr, trf, Key},
+  settings,
+};
+
+use super::{basic_button, palette, with_icon, Component};
+
+#[derive(Debug, Default)]
+pub struct CurriculumView {
+  source: String,
+  rows: Vec<Row>,
+  error: Option<String>,
+  only_mismatches: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+  OnlyMismatches(bool),
+  Reload,
+}
+
+impl CurriculumView {
+  pub fn set(&mut self, result: Result<(String, Vec<Row>), String>) {
+    match result {
+      Ok((source, rows)) => {
+        self.source = source;
+        self.rows = rows;
+        self.error = None;
+      }
+      Err(error) => {
+        self.rows.clear();
+        self.error = Some(error);
+      }
+    }
+  }
+
+  fn row_view(row: &Row) -> Element<Message> {
+    let difference = text(format!("{:+.0}", row.difference())).width(Length::Fixed(80.));
+    let difference = match row.status() {
+      Status::Under => difference.style(theme::Text::Color(palette::current().danger)),
+      Status::Over => difference.style(theme::Text::Color(palette::current().primary)),
+      Status::Matches => difference,
+    };
+    row![
+      text(&row.subject).width(Length::Fill),
+      text(format!("{:.0}", row.required)).width(Length::Fixed(80.)),
+      text(format!("{:.0}", row.scheduled)).width(Length::Fixed(80.)),
+      difference,
+    ]
+    .spacing(10)
+    .into()
+  }
+}
+
+impl Component for CurriculumView {
+  type Message = Message;
+
+  fn update(&mut self, message: Self::Message) {
+    match message {
+      Message::OnlyMismatches(value) => self.only_mismatches = value,
+      Message::Reload => (),
+    }
+  }
+
+  fn view(&self) -> Element<Self::Message> {
+    let header = row![
+      checkbox(tr(Key::OnlyMismatches), self.only_mismatches, Message::OnlyMismatches).width(Length::Fill),
+      basic_button(with_icon(tr(Key::Reload), Icon::ArrowClockwise), Message::Reload),
+    ]
+    .align_items(iced::Alignment::Center)
+    .spacing(10);
+
+    if let Some(error) = &self.error {
+      let content = column![header, text(error).style(palette::error_text())]
+        .spacing(10)
+        .padding([10, 15]);
+      return container(content).width(Length::Fill).into();
+    }
+
+    let columns = row![
+      text(tr(Key::Subject)).width(Length::Fill),
+      text(tr(Key::Required)).width(Length::Fixed(80.)),
+      text(tr(Key::Scheduled)).width(Length::Fixed(80.)),
+      text(tr(Key::Difference)).width(Length::Fixed(80.)),
+    ]
+    .spacing(10);
+
+    let mut groups = vec![];
+    let shown = self
+      .rows
+      .iter()
+      .filter(|r| !self.only_mismatches || r.status() != Status::Matches)
+      .collect::<Vec<_>>();
+    for (idx, row) in shown.iter().enumerate() {
+      if idx == 0 || shown[idx - 1].group != row.group {
+        groups.push(text(&row.group).size(20).into());
+      }
+      groups.push(Self::row_view(row));
+    }
+
+    let (start, end) = settings::get(|s| (s.semester_start, s.semester_end));
+    let source =
+      trf(Key::CurriculumSource, &[&self.source, &start.format("%d.%m.%Y"), &end.format("%d.%m.%Y"), &curriculum::LESSON_HOURS]);
+    let content = column![header, text(source).size(14), columns, Rule::horizontal(1), scrollable(column(groups).spacing(5))]
+      .spacing(10)
+      .padding([10, 15]);
+    container(content).width(Length::Fill).into()
+  }
+}
@@ -1,8 +1,12 @@
-use super::lesson::LessonComponent;
-use super::{icon_button, Component, LessonMessage};
-use iced::theme::Button;
+use super::lesson::{InvalidInput, LessonComponent};
+use super::{icon_button, palette, Component, LessonMessage};
+use crate::dictionary::{Dictionary, Field};
+use crate::i18n::{tr, Key};
+use crate::rotation::WeekPattern;
+use crate::{ordering, search};
+use iced::theme::{Button, Container};
+use iced::widget::{checkbox, text, text_input, Row};
 use iced::widget::{column, container, rule::Rule};
-use iced::widget::{row, text_input};
 use iced::{Element, Length};
 use iced_aw::Icon;
 use maiq_shared::default::{DefaultGroup, DefaultLesson};
@@ -10,13 +14,38 @@ use maiq_shared::default::{DefaultGroup, DefaultLesson};
 pub trait GroupComponent {
   fn update_lesson(&mut self, idx: usize, message: LessonMessage);
   fn remove_lesson(&mut self, idx: usize);
+  fn move_lesson(&mut self, idx: usize, up: bool) -> Option<usize>;
+  fn view_with(&self, options: &ViewOptions) -> Element<Message>;
+}
+
+#[derive(Default)]
+pub struct ViewOptions<'a> {
+  pub focus: Option<(usize, Field)>,
+  pub dictionary: Option<&'a Dictionary>,
+  pub search: &'a str,
+  pub selected: Vec<usize>,
+  // Selection and paste work with the editor's own state, other editors leave them out
+  pub selectable: bool,
+  // Week parity and patterns belong to the defaults, a change is for one date
+  pub parity: bool,
+  pub can_paste: bool,
+  pub invalid: Vec<(usize, InvalidInput)>,
+  pub patterns: Vec<(usize, WeekPattern)>,
+  // Gaps shown under the lesson they follow
+  pub gaps: Vec<(usize, String)>,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
   EditName(String),
   Lesson((usize, LessonMessage)),
+  Select(usize, bool),
+  SelectAll(bool),
   CreateLesson,
+  Duplicate,
+  Paste,
+  MoveUp,
+  MoveDown,
   Remove,
 }
 
@@ -30,40 +59,91 @@ impl GroupComponent for DefaultGroup {
   fn remove_lesson(&mut self, idx: usize) {
     self.lessons.remove(idx);
   }
-}
 
-impl Component for DefaultGroup {
-  type Message = Message;
-
-  fn update(&mut self, message: Message) {
-    match message {
-      Message::EditName(name) => self.name = name,
-      Message::CreateLesson => self.lessons.push(DefaultLesson::new(self.lessons.last())),
-      Message::Lesson((idx, LessonMessage::Remove)) => self.remove_lesson(idx),
-      Message::Lesson((idx, message)) => self.update_lesson(idx, message),
-      _ => (),
-    }
+  fn move_lesson(&mut self, idx: usize, up: bool) -> Option<usize> {
+    ordering::shift(&mut self.lessons, idx, up)
   }
 
-  fn view(&self) -> Element<Self::Message> {
-    let name_field = text_input("Группа", &self.name.to_string(), Message::EditName).width(Length::Fixed(80.));
-    let header = row![
-      name_field,
-      icon_button(Icon::Plus).on_press(Message::CreateLesson),
-      icon_button(Icon::Trash)
-        .on_press(Message::Remove)
-        .style(Button::Destructive),
-    ]
-    .align_items(iced::Alignment::Center)
-    .spacing(20)
-    .padding([0, 10, 10, 15]);
+  fn view_with(&self, options: &ViewOptions) -> Element<Message> {
+    let name_field = text_input(tr(Key::Group), &self.name.to_string(), Message::EditName).width(Length::Fixed(80.));
+    let all_selected = !self.lessons.is_empty() && options.selected.len() == self.lessons.len();
+    let mut paste = icon_button(Icon::Clipboard);
+    if options.can_paste {
+      paste = paste.on_press(Message::Paste);
+    }
+    let mut header = Row::new();
+    if options.selectable {
+      header = header.push(checkbox("", all_selected, Message::SelectAll));
+    }
+    header = header
+      .push(name_field)
+      .push(icon_button(Icon::Plus).on_press(Message::CreateLesson))
+      .push(icon_button(Icon::Files).on_press(Message::Duplicate));
+    if options.selectable {
+      header = header.push(paste);
+    }
+    let header = header
+      .push(icon_button(Icon::ArrowUp).on_press(Message::MoveUp))
+      .push(icon_button(Icon::ArrowDown).on_press(Message::MoveDown))
+      .push(
+        icon_button(Icon::Trash)
+          .on_press(Message::Remove)
+          .style(Button::Destructive),
+      )
+      .align_items(iced::Alignment::Center)
+      .spacing(20)
+      .padding([0, 10, 10, 15]);
 
     let lessons = column(
       self
         .lessons
         .iter()
         .enumerate()
-        .map(|(idx, l)| l.view().map(move |msg| Message::Lesson((idx, msg))))
+        .map(|(idx, l)| {
+          let invalid = options
+            .invalid
+            .iter()
+            .filter(|(i, _)| *i == idx)
+            .map(|(_, i)| i)
+            .collect::<Vec<_>>();
+          let pattern = options.patterns.iter().find(|(i, _)| *i == idx).map(|(_, p)| p);
+          let lesson = match search::matches_lesson(l, options.search) {
+            true => container(l.view_with(&invalid, pattern, options.parity))
+              .style(Container::Box)
+              .into(),
+            false => l.view_with(&invalid, pattern, options.parity),
+          };
+          let suggestions = options
+            .focus
+            .filter(|(focused, _)| *focused == idx)
+            .zip(options.dictionary)
+            .and_then(|((_, field), dictionary)| l.view_suggestions(field, dictionary));
+          let lesson = match suggestions {
+            Some(suggestions) => column![lesson, suggestions].spacing(5).into(),
+            None => lesson,
+          }
+          .map(move |msg| Message::Lesson((idx, msg)));
+          let mut entry = Row::new();
+          if options.selectable {
+            entry = entry.push(checkbox("", options.selected.contains(&idx), move |v| Message::Select(idx, v)));
+          }
+          let lesson = entry
+            .push(lesson)
+            .align_items(iced::Alignment::Center)
+            .padding([0, 0, 0, 15]);
+          let gaps = options
+            .gaps
+            .iter()
+            .filter(|(i, _)| *i == idx)
+            .map(|(_, gap)| text(gap).size(12).style(palette::error_text()).into())
+            .collect::<Vec<_>>();
+          match gaps.is_empty() {
+            true => lesson.into(),
+            false => column![lesson, column(gaps).spacing(2).padding([0, 0, 0, 45])]
+              .spacing(5)
+              .into(),
+          }
+        })
         .collect(),
     )
     .spacing(10)
@@ -73,3 +153,27 @@ impl Component for DefaultGroup {
     container(content).into()
   }
 }
+
+impl Component for DefaultGroup {
+  type Message = Message;
+
+  fn update(&mut self, message: Message) {
+    match message {
+      Message::EditName(name) => self.name = name,
+      Message::CreateLesson => self.lessons.push(DefaultLesson::new(self.lessons.last())),
+      Message::Lesson((idx, LessonMessage::Remove)) => self.remove_lesson(idx),
+      Message::Lesson((idx, LessonMessage::MoveUp)) => {
+        self.move_lesson(idx, true);
+      }
+      Message::Lesson((idx, LessonMessage::MoveDown)) => {
+        self.move_lesson(idx, false);
+      }
+      Message::Lesson((idx, message)) => self.update_lesson(idx, message),
+      _ => (),
+    }
+  }
+
+  fn view(&self) -> Element<Self::Message> {
+    self.view_with(&ViewOptions::default())
+  }
+}
@@ -1,21 +1,43 @@
 use std::{
-  fs::{self, File},
-  io::BufWriter,
+  collections::{BTreeMap, BTreeSet},
+  fs,
+  future::Future,
   path::Path,
   slice::Iter,
 };
 
 use iced::widget::{container, row, text};
 use iced_aw::Icon;
-use maiq_shared::default::{DefaultDay, DefaultGroup};
+use maiq_shared::default::{DefaultDay, DefaultGroup, DefaultLesson};
 
-use crate::env;
+use crate::{
+  dictionary::Field,
+  env, format, gaps, history,
+  i18n::{trf, Key},
+  ordering::{self, SortOrder},
+  references::{self, Kind},
+  rotation::{Rotations, WeekPattern},
+  search,
+};
 
-use super::{icon_button, Component, GroupMessage};
+use super::{
+  icon_button,
+  lesson::{ForDay, InvalidInput, LessonComponent, ValidatedField},
+  toolbar::map_weekday_to_str,
+  Component, GroupMessage, LessonMessage,
+};
 
 #[derive(Debug)]
 pub struct SnapshotEditor {
   snapshot: DefaultDay,
+  focus: Option<(usize, usize, Field)>,
+  // The group and lesson last acted on; iced doesn't tell which input has the keyboard focus
+  cursor: Option<(usize, Option<usize>)>,
+  selection: BTreeSet<(usize, usize)>,
+  clipboard: Vec<DefaultLesson>,
+  invalid: BTreeMap<(usize, usize, ValidatedField), InvalidInput>,
+  rotations: Rotations,
+  source: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -26,7 +48,16 @@ pub enum Message {
 
 impl Default for SnapshotEditor {
   fn default() -> Self {
-    Self { snapshot: DefaultDay { day: chrono::Weekday::Mon, groups: vec![] } }
+    Self {
+      snapshot: DefaultDay { day: chrono::Weekday::Mon, groups: vec![] },
+      focus: None,
+      cursor: None,
+      selection: BTreeSet::new(),
+      clipboard: vec![],
+      invalid: BTreeMap::new(),
+      rotations: Rotations::default(),
+      source: None,
+    }
   }
 }
 
@@ -35,6 +66,265 @@ impl SnapshotEditor {
     self.snapshot.groups.iter()
   }
 
+  pub fn snapshot(&self) -> &DefaultDay {
+    &self.snapshot
+  }
+
+  pub fn source(&self) -> Option<&str> {
+    self.source.as_deref()
+  }
+
+  // The weekday whose saved default the editor holds; `None` until one is loaded
+  pub fn loaded_day(&self) -> Option<chrono::Weekday> {
+    self.source.as_ref().map(|_| self.snapshot.day)
+  }
+
+  pub fn cursor(&self) -> Option<(usize, Option<usize>)> {
+    self.cursor
+  }
+
+  // Tab moves the keyboard focus away from the lesson, which then is no longer the one to act on
+  pub fn leave_lesson(&mut self) {
+    self.focus = None;
+    self.cursor = self.cursor.map(|(group, _)| (group, None));
+  }
+
+  pub fn focus_in(&self, group: usize) -> Option<(usize, Field)> {
+    self
+      .focus
+      .filter(|(focused, ..)| *focused == group)
+      .map(|(_, lesson, field)| (lesson, field))
+  }
+
+  pub fn selected_in(&self, group: usize) -> Vec<usize> {
+    self
+      .selection
+      .range((group, 0)..(group + 1, 0))
+      .map(|(_, lesson)| *lesson)
+      .collect()
+  }
+
+  pub fn selected(&self) -> Vec<(&DefaultGroup, &DefaultLesson)> {
+    self
+      .selection
+      .iter()
+      .filter_map(|(group, lesson)| {
+        let group = self.snapshot.groups.get(*group)?;
+        Some((group, group.lessons.get(*lesson)?))
+      })
+      .collect()
+  }
+
+  pub fn selection(&self) -> Vec<(usize, usize)> {
+    self.selection.iter().copied().collect()
+  }
+
+  pub fn invalid_in(&self, group: usize) -> Vec<(usize, InvalidInput)> {
+    self
+      .invalid
+      .iter()
+      .filter(|((g, ..), _)| *g == group)
+      .map(|((_, lesson, _), invalid)| (*lesson, invalid.clone()))
+      .collect()
+  }
+
+  pub fn rotations(&self) -> &Rotations {
+    &self.rotations
+  }
+
+  pub fn rotations_mut(&mut self) -> &mut Rotations {
+    &mut self.rotations
+  }
+
+  pub fn set_rotations(&mut self, rotations: Rotations) {
+    self.rotations = rotations;
+  }
+
+  pub fn patterns_in(&self, group: usize) -> Vec<(usize, WeekPattern)> {
+    let Some(g) = self.snapshot.groups.get(group) else {
+      return vec![];
+    };
+    g.lessons
+      .iter()
+      .enumerate()
+      .filter_map(|(idx, l)| Some((idx, self.rotations.get(self.snapshot.day, &g.name, l)?.clone())))
+      .collect()
+  }
+
+  // Each gap is attached to the closest lesson before it that the gap's subgroup and week attend
+  pub fn gaps_in(&self, group: usize) -> Vec<(usize, String)> {
+    let Some(g) = self.snapshot.groups.get(group) else {
+      return vec![];
+    };
+    gaps::group_gaps(self.snapshot.day, g)
+      .into_iter()
+      .filter_map(|gap| {
+        let (idx, _) = g
+          .lessons
+          .iter()
+          .enumerate()
+          .filter(|(_, l)| l.num < gap.from)
+          .filter(|(_, l)| gap.subgroup.is_none_or(|s| l.subgroup.is_none_or(|ls| ls == s)))
+          .filter(|(_, l)| gap.week.is_none_or(|w| w.includes(l)))
+          .max_by_key(|(_, l)| l.num)?;
+        Some((idx, gap.to_string()))
+      })
+      .collect()
+  }
+
+  pub fn clipboard(&self) -> &[DefaultLesson] {
+    &self.clipboard
+  }
+
+  pub fn copy_selection(&mut self) -> usize {
+    self.clipboard = self
+      .selected()
+      .into_iter()
+      .map(|(_, lesson)| lesson.clone())
+      .collect();
+    self.clipboard.len()
+  }
+
+  pub fn paste(&mut self, idx: usize) {
+    if let Some(g) = self.snapshot.groups.get_mut(idx) {
+      g.lessons.extend(self.clipboard.iter().cloned())
+    }
+  }
+
+  pub fn add_lessons(&mut self, lessons: &[(String, DefaultLesson)]) -> usize {
+    add_lessons(&mut self.snapshot, lessons)
+  }
+
+  pub fn lesson(&self, group: usize, lesson: usize) -> Option<(&DefaultGroup, &DefaultLesson)> {
+    let g = self.snapshot.groups.get(group)?;
+    Some((g, g.lessons.get(lesson)?))
+  }
+
+  // A rotation only follows the lesson if its parity stays the same
+  pub fn move_lesson(&mut self, group: usize, lesson: usize, num: u8, is_even: Option<bool>, classroom: Option<String>) {
+    let day = self.snapshot.day;
+    let Some(g) = self.snapshot.groups.get_mut(group) else {
+      return;
+    };
+    let Some(l) = g.lessons.get_mut(lesson) else {
+      return;
+    };
+
+    match l.is_even == is_even {
+      true => self
+        .rotations
+        .rekey(day, (&g.name, l.num, l.subgroup, l.is_even), (&g.name, num, l.subgroup, is_even)),
+      false => self.rotations.set(day, &g.name, l, None),
+    }
+    (l.num, l.is_even, l.classroom) = (num, is_even, classroom);
+    self.invalid.clear();
+  }
+
+  pub fn set_teacher(&mut self, group: usize, lesson: usize, teacher: &str) {
+    if let Some(l) = self
+      .snapshot
+      .groups
+      .get_mut(group)
+      .and_then(|g| g.lessons.get_mut(lesson))
+    {
+      l.teacher = Some(teacher.to_string());
+    }
+  }
+
+  pub fn take_lesson(&mut self, group: usize, lesson: usize) -> Option<DefaultLesson> {
+    let day = self.snapshot.day;
+    let g = self.snapshot.groups.get_mut(group)?;
+    if lesson >= g.lessons.len() {
+      return None;
+    }
+    let l = g.lessons.remove(lesson);
+    self.rotations.set(day, &g.name, &l, None);
+    self.focus = None;
+    self.cursor = Some((group, None));
+    self.selection.clear();
+    self.invalid.clear();
+    Some(l)
+  }
+
+  pub fn duplicate_group(&mut self, idx: usize) {
+    if let Some(g) = self.snapshot.groups.get(idx) {
+      let copy = DefaultGroup { name: trf(Key::GroupCopy, &[&g.name]), ..g.clone() };
+      self.snapshot.groups.insert(idx + 1, copy);
+    }
+  }
+
+  fn edit_number(&mut self, group: usize, lesson: usize, field: ValidatedField, input: String) {
+    let day = self.snapshot.day;
+    let Some(g) = self.snapshot.groups.get_mut(group) else {
+      return;
+    };
+    let Some(l) = g.lessons.get_mut(lesson) else {
+      return;
+    };
+
+    let before = (l.num, l.subgroup);
+    let is_even = l.is_even;
+    let result = match field {
+      ValidatedField::Num => l.set_num(&input),
+      ValidatedField::Subgroup => l.set_subgroup(&input),
+      ValidatedField::Pattern => return self.edit_pattern(group, lesson, input),
+    };
+    match result {
+      Ok(_) => {
+        self
+          .rotations
+          .rekey(day, (&g.name, before.0, before.1, is_even), (&g.name, l.num, l.subgroup, is_even));
+        self.invalid.remove(&(group, lesson, field));
+      }
+      Err(error) => {
+        _ = self
+          .invalid
+          .insert((group, lesson, field), InvalidInput { field, input, error })
+      }
+    }
+  }
+
+  fn edit_pattern(&mut self, group: usize, lesson: usize, input: String) {
+    let field = ValidatedField::Pattern;
+    match WeekPattern::parse(&input) {
+      Ok(pattern) => {
+        self.set_pattern(group, lesson, Some(pattern));
+        self.invalid.remove(&(group, lesson, field));
+      }
+      Err(error) => {
+        _ = self
+          .invalid
+          .insert((group, lesson, field), InvalidInput { field, input, error })
+      }
+    }
+  }
+
+  fn set_pattern(&mut self, group: usize, lesson: usize, pattern: Option<WeekPattern>) {
+    let day = self.snapshot.day;
+    let Some(g) = self.snapshot.groups.get_mut(group) else {
+      return;
+    };
+    let Some(l) = g.lessons.get_mut(lesson) else {
+      return;
+    };
+
+    // The fallback may change the parity, which is part of the rotation's key
+    self.rotations.set(day, &g.name, l, None);
+    if let Some(pattern) = &pattern {
+      l.is_even = pattern.fallback();
+    }
+    self.rotations.set(day, &g.name, l, pattern);
+  }
+
+  fn select(&mut self, group: usize, lessons: impl Iterator<Item = usize>, selected: bool) {
+    for lesson in lessons {
+      match selected {
+        true => self.selection.insert((group, lesson)),
+        false => self.selection.remove(&(group, lesson)),
+      };
+    }
+  }
+
   pub fn update_group(&mut self, message: GroupMessage, idx: usize) {
     if let Some(g) = self.snapshot.groups.get_mut(idx) {
       g.update(message)
@@ -45,44 +335,69 @@ impl SnapshotEditor {
     self.snapshot.groups.push(DefaultGroup::default())
   }
 
+  // Patterns of a removed lesson or group would otherwise pass on to whatever takes its place
   pub fn remove_group(&mut self, idx: usize) {
-    self.snapshot.groups.remove(idx);
+    if idx >= self.snapshot.groups.len() {
+      return;
+    }
+    let g = self.snapshot.groups.remove(idx);
+    if self.snapshot.groups.iter().all(|other| other.name != g.name) {
+      self.rotations.remove_group(self.snapshot.day, &g.name);
+    }
   }
 
-  pub fn sort(&mut self) -> anyhow::Result<Option<String>> {
-    self.snapshot.groups.sort_by(|a, b| a.name.cmp(&b.name));
+  pub fn rename(&mut self, kind: Kind, from: &str, to: &str) -> usize {
+    if kind == Kind::Group {
+      env::WEEKDAYS
+        .into_iter()
+        .for_each(|day| self.rotations.rename_group(day, from, to));
+    }
+    references::rename(kind, from, to, &mut self.snapshot)
+  }
+
+  pub fn replace(&mut self, find: &str, replacement: &str) -> usize {
+    for (from, to) in search::renamed_groups(&self.snapshot, find, replacement) {
+      self.rotations.rename_group(self.snapshot.day, &from, &to);
+    }
+    search::replace(&mut self.snapshot, find, replacement)
+  }
+
+  pub fn move_group(&mut self, idx: usize, up: bool) -> Option<usize> {
+    ordering::shift(&mut self.snapshot.groups, idx, up)
+  }
+
+  pub fn sort(&mut self, order: SortOrder) -> anyhow::Result<Option<String>> {
+    self.snapshot.groups.sort_by(|a, b| order.compare(&a.name, &b.name));
     self
       .snapshot
       .groups
       .iter_mut()
-      .for_each(|g| g.lessons.sort_by_key(|k| k.num));
-    Ok(Some("Отсортировано".into()))
+      .for_each(|g| g.lessons.sort_by_key(|k| (k.num, k.subgroup)));
+    self.focus = None;
+    self.cursor = None;
+    self.selection.clear();
+    self.invalid.clear();
+    Ok(Some(trf(Key::Sorted, &[&order.to_string().to_lowercase()])))
   }
 
-  // fn update_date(&mut self) {
-  //   let (d, m, y) = (self.date.day, self.date.month, self.date.year);
-  //   let date = chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
-  //   let date_utc = chrono::DateTime::<Utc>::from_local(NaiveDateTime::new(date, NaiveTime::default()), Utc);
-  //   self.snapshot.date = date_utc;
-  // }
-
-  pub fn save_to_file(&mut self) -> anyhow::Result<Option<String>> {
-    let dir = &*env::export_dir();
-    if Path::new(dir).metadata().is_err() {
-      fs::create_dir(dir).unwrap();
-    }
-    // self.snapshot.uid = self.snapshot.uid();
-    let filename = format!("{}/{}.json", dir, self.snapshot.day.to_string().to_lowercase());
-    let file = File::create(&filename).unwrap();
-    let writer = BufWriter::new(file);
-    serde_json::to_writer_pretty(writer, &self.snapshot).unwrap();
-    println!("Exported to {}", filename);
-    Ok(Some(format!("Экспортировано в {}", filename)))
+  // Files are written on the executor so the UI doesn't stall on slow disks
+  pub fn export(&self) -> impl Future<Output = Result<Option<String>, String>> {
+    let (snapshot, rotations, dir) = (self.snapshot.clone(), self.rotations.clone(), env::export_dir());
+    async move { write_export(&snapshot, &rotations, &dir).map_err(|err| err.to_string()) }
   }
 
   pub fn set_groups(&mut self, day: &DefaultDay) -> anyhow::Result<Option<String>> {
-    self.snapshot = day.clone();
-    Ok(Some(format!("Загружен: {:?}", self.snapshot.day)))
+    self.restore(day.clone(), Some(env::default_path(day.day)));
+    Ok(Some(trf(Key::Loaded, &[&map_weekday_to_str(self.snapshot.day)])))
+  }
+
+  pub fn restore(&mut self, snapshot: DefaultDay, source: Option<String>) {
+    self.snapshot = snapshot;
+    self.source = source;
+    self.focus = None;
+    self.cursor = None;
+    self.selection.clear();
+    self.invalid.clear();
   }
 }
 
@@ -90,19 +405,146 @@ impl Component for SnapshotEditor {
   type Message = Message;
 
   fn update(&mut self, message: Self::Message) {
+    self.focus = match &message {
+      Message::Group((group, GroupMessage::Lesson((lesson, msg)))) => msg.edited_field().map(|f| (*group, *lesson, f)),
+      _ => None,
+    };
+
+    self.cursor = match &message {
+      Message::Group((_, GroupMessage::Remove)) => None,
+      Message::Group((group, GroupMessage::Lesson((_, LessonMessage::Remove)))) => Some((*group, None)),
+      Message::Group((group, GroupMessage::Lesson((lesson, LessonMessage::MoveUp)))) => {
+        Some((*group, Some(lesson.saturating_sub(1))))
+      }
+      Message::Group((group, GroupMessage::Lesson((lesson, LessonMessage::MoveDown)))) => {
+        let last = self
+          .snapshot
+          .groups
+          .get(*group)
+          .map(|g| g.lessons.len().saturating_sub(1))
+          .unwrap_or_default();
+        Some((*group, Some((*lesson + 1).min(last))))
+      }
+      Message::Group((group, GroupMessage::Duplicate)) => Some((*group + 1, None)),
+      Message::Group((group, GroupMessage::MoveUp)) => Some((group.saturating_sub(1), None)),
+      Message::Group((group, GroupMessage::MoveDown)) => {
+        Some(((*group + 1).min(self.snapshot.groups.len().saturating_sub(1)), None))
+      }
+      Message::Group((group, GroupMessage::Lesson((lesson, _)))) => Some((*group, Some(*lesson))),
+      Message::Group((group, _)) => Some((*group, None)),
+      Message::CreateGroup => Some((self.snapshot.groups.len(), None)),
+    };
+
+    let reorders = matches!(
+      &message,
+      Message::Group((_, GroupMessage::Remove | GroupMessage::Duplicate | GroupMessage::MoveUp | GroupMessage::MoveDown))
+        | Message::Group((_, GroupMessage::Lesson((_, LessonMessage::Remove | LessonMessage::MoveUp | LessonMessage::MoveDown))))
+    );
+    if reorders {
+      self.selection.clear();
+      self.invalid.clear();
+    }
+
     match message {
+      Message::Group((idx, GroupMessage::Select(lesson, selected))) => self.select(idx, std::iter::once(lesson), selected),
+      Message::Group((idx, GroupMessage::SelectAll(selected))) => {
+        let count = self
+          .snapshot
+          .groups
+          .get(idx)
+          .map(|g| g.lessons.len())
+          .unwrap_or_default();
+        self.select(idx, 0..count, selected)
+      }
+      Message::Group((group, GroupMessage::Lesson((lesson, LessonMessage::EditNum(input))))) => {
+        self.edit_number(group, lesson, ValidatedField::Num, input)
+      }
+      Message::Group((group, GroupMessage::Lesson((lesson, LessonMessage::EditSubgroup(input))))) => {
+        self.edit_number(group, lesson, ValidatedField::Subgroup, input)
+      }
+      Message::Group((group, GroupMessage::Lesson((lesson, LessonMessage::EditPattern(input))))) => {
+        self.edit_pattern(group, lesson, input)
+      }
+      Message::Group((group, GroupMessage::Lesson((lesson, LessonMessage::ForDaySelected(ForDay::Custom))))) => {
+        if self.patterns_in(group).iter().all(|(idx, _)| *idx != lesson) {
+          self.set_pattern(group, lesson, Some(WeekPattern::default()))
+        }
+      }
+      Message::Group((group, GroupMessage::Lesson((lesson, LessonMessage::ForDaySelected(for_day))))) => {
+        self.set_pattern(group, lesson, None);
+        self.invalid.remove(&(group, lesson, ValidatedField::Pattern));
+        self.update_group(GroupMessage::Lesson((lesson, LessonMessage::ForDaySelected(for_day))), group)
+      }
+      Message::Group((idx, GroupMessage::EditName(name))) => {
+        if let Some(old) = self.snapshot.groups.get(idx).map(|g| g.name.clone()) {
+          self.rotations.rename_group(self.snapshot.day, &old, &name);
+        }
+        self.update_group(GroupMessage::EditName(name), idx)
+      }
+      Message::Group((group, GroupMessage::Lesson((lesson, LessonMessage::Remove)))) => {
+        _ = self.take_lesson(group, lesson);
+      }
+      Message::Group((idx, GroupMessage::Paste)) => self.paste(idx),
+      Message::Group((idx, GroupMessage::Duplicate)) => self.duplicate_group(idx),
       Message::Group((idx, GroupMessage::Remove)) => self.remove_group(idx),
+      Message::Group((idx, GroupMessage::MoveUp)) => {
+        self.move_group(idx, true);
+      }
+      Message::Group((idx, GroupMessage::MoveDown)) => {
+        self.move_group(idx, false);
+      }
       Message::Group((idx, msg)) => self.update_group(msg, idx),
       Message::CreateGroup => self.create_group(),
     }
   }
 
   fn view(&self) -> iced::Element<Self::Message> {
-    let content = row![icon_button(Icon::Plus).on_press(Message::CreateGroup), text(&format!("День: {}", self.snapshot.day))]
-      .align_items(iced::Alignment::Center)
-      .spacing(25)
-      .padding(5);
+    let content = row![
+      icon_button(Icon::Plus).on_press(Message::CreateGroup),
+      text(trf(Key::DayLabel, &[&map_weekday_to_str(self.snapshot.day)]))
+    ]
+    .align_items(iced::Alignment::Center)
+    .spacing(25)
+    .padding(5);
 
     container(content).into()
   }
 }
+
+// Lessons are appended to the groups with the given names, missing groups are created
+pub fn add_lessons(day: &mut DefaultDay, lessons: &[(String, DefaultLesson)]) -> usize {
+  for (name, lesson) in lessons {
+    let idx = match day.groups.iter().position(|g| g.name == *name) {
+      Some(idx) => idx,
+      None => {
+        day
+          .groups
+          .push(DefaultGroup { name: name.clone(), ..DefaultGroup::default() });
+        day.groups.len() - 1
+      }
+    };
+    day.groups[idx].lessons.push(lesson.clone());
+  }
+  lessons.len()
+}
+
+fn write_export(snapshot: &DefaultDay, rotations: &Rotations, dir: &str) -> anyhow::Result<Option<String>> {
+  if Path::new(dir).metadata().is_err() {
+    fs::create_dir_all(dir)?;
+  }
+  let filename = format!("{}/{}.json", dir, snapshot.day.to_string().to_lowercase());
+  fs::write(&filename, format::to_string(snapshot)?)?;
+  format::write_schema(dir)?;
+  println!("Exported to {}", filename);
+  history::record(dir, snapshot)?;
+
+  let approximated = rotations
+    .for_day(snapshot.day)
+    .filter(|r| !r.pattern.is_exact())
+    .count();
+  rotations.save()?;
+  match approximated {
+    0 => Ok(Some(trf(Key::Exported, &[&filename]))),
+    n => Ok(Some(trf(Key::ExportedApproximated, &[&filename, &n]))),
+  }
+}
@@ -0,0 +1,177 @@
+use std::fmt::Display;
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use iced::{
+  theme,
+  widget::{column, container, pick_list, row, scrollable, text},
+  Element, Length,
+};
+use iced_aw::{date_picker::Date, DatePicker, Icon};
+
+use crate::{
+  compose::{self, FinalGroup, Sources},
+  diff, env,
+  i18n::{tr, trf, Key},
+  rotation, settings,
+};
+
+use super::{basic_button, palette, toolbar::map_weekday_to_str, with_icon, Component};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SubgroupFilter {
+  #[default]
+  All,
+  Only(u8),
+}
+
+impl SubgroupFilter {
+  fn options() -> Vec<Self> {
+    std::iter::once(SubgroupFilter::All)
+      .chain((1..=env::max_subgroups()).map(SubgroupFilter::Only))
+      .collect()
+  }
+
+  fn subgroup(self) -> Option<u8> {
+    match self {
+      SubgroupFilter::All => None,
+      SubgroupFilter::Only(n) => Some(n),
+    }
+  }
+}
+
+impl Display for SubgroupFilter {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      SubgroupFilter::All => write!(f, "{}", tr(Key::AllSubgroups)),
+      SubgroupFilter::Only(n) => write!(f, "{}", trf(Key::SubgroupN, &[n])),
+    }
+  }
+}
+
+// Read-only: what students get for the date once the changes are published
+#[derive(Debug)]
+pub struct FinalDayView {
+  date: NaiveDate,
+  shown: NaiveDate,
+  show_picker: bool,
+  subgroup: SubgroupFilter,
+  groups: Vec<FinalGroup>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+  OpenPicker,
+  CancelPicker,
+  PickDate(Date),
+  Today,
+  Tomorrow,
+  SelectSubgroup(SubgroupFilter),
+}
+
+impl Default for FinalDayView {
+  fn default() -> Self {
+    let date = Local::now().date_naive();
+    Self { date, shown: date, show_picker: false, subgroup: SubgroupFilter::All, groups: vec![] }
+  }
+}
+
+impl FinalDayView {
+  pub fn date(&self) -> NaiveDate {
+    self.date
+  }
+
+  // `shown` differs from the picked date when that one has no timetable of its own, e.g. on Sunday
+  pub fn refresh(&mut self, shown: NaiveDate, sources: &Sources) {
+    self.shown = shown;
+    self.groups = compose::compose(shown, sources, self.subgroup.subgroup());
+  }
+
+  fn group_view(group: &FinalGroup) -> Element<Message> {
+    let accent = theme::Text::Color(palette::current().primary);
+    let mut name = text(&group.name).size(20);
+    if group.changed {
+      name = name.style(accent);
+    }
+
+    let lessons = match group.lessons.is_empty() {
+      true => vec![text(tr(Key::NoLessons)).size(14).into()],
+      false => group
+        .lessons
+        .iter()
+        .map(|l| {
+          let line = text(diff::describe(&l.lesson)).size(16);
+          match l.changed {
+            true => row![text("●").style(accent), line.style(accent)].spacing(5).into(),
+            false => row![text(" "), line].spacing(5).into(),
+          }
+        })
+        .collect(),
+    };
+
+    column![name, column(lessons).spacing(2)].spacing(5).into()
+  }
+}
+
+impl Component for FinalDayView {
+  type Message = Message;
+
+  fn update(&mut self, message: Self::Message) {
+    match message {
+      Message::OpenPicker => self.show_picker = true,
+      Message::CancelPicker => self.show_picker = false,
+      Message::PickDate(date) => {
+        self.show_picker = false;
+        if let Some(date) = NaiveDate::from_ymd_opt(date.year, date.month, date.day) {
+          self.date = date;
+        }
+      }
+      Message::Today => self.date = Local::now().date_naive(),
+      Message::Tomorrow => self.date = Local::now().date_naive() + Duration::days(1),
+      Message::SelectSubgroup(subgroup) => self.subgroup = subgroup,
+    }
+  }
+
+  fn view(&self) -> Element<Self::Message> {
+    let label = format!("{} ({})", self.date.format("%d.%m.%Y"), map_weekday_to_str(self.date.weekday()));
+    let picker = DatePicker::new(
+      self.show_picker,
+      self.date,
+      basic_button(with_icon(text(label), Icon::Calendar), Message::OpenPicker),
+      Message::CancelPicker,
+      Message::PickDate,
+    );
+
+    let week = rotation::week_number(self.shown, settings::get(|s| s.parity_anchor));
+    let parity = match rotation::is_even_week(week) {
+      true => tr(Key::Even),
+      false => tr(Key::Odd),
+    };
+
+    let header = row![
+      text(tr(Key::FinalDay)),
+      picker,
+      basic_button(text(tr(Key::Today)), Message::Today),
+      basic_button(text(tr(Key::Tomorrow)), Message::Tomorrow),
+      pick_list(SubgroupFilter::options(), Some(self.subgroup), Message::SelectSubgroup),
+      text(trf(Key::WeekLabel, &[&week, &parity.to_lowercase()])).size(14),
+    ]
+    .align_items(iced::Alignment::Center)
+    .spacing(10);
+
+    let groups = match self.groups.is_empty() {
+      true => column![text(tr(Key::NoLessons))],
+      false => column(self.groups.iter().map(Self::group_view).collect()),
+    }
+    .spacing(15);
+
+    let mut notes = column![text(tr(Key::ChangedLegend)).size(14)];
+    if self.shown != self.date {
+      let shown = format!("{} ({})", self.shown.format("%d.%m.%Y"), map_weekday_to_str(self.shown.weekday()));
+      notes = notes.push(text(trf(Key::ShownInstead, &[&shown])).size(14));
+    }
+    let content = column![header, notes, scrollable(groups)]
+      .spacing(10)
+      .padding([10, 15]);
+    container(content).width(Length::Fill).into()
+  }
+}
@@ -0,0 +1,79 @@
+use iced::{
+  widget::{column, container, row, scrollable, text, Rule},
+  Element, Length,
+};
+use maiq_shared::default::DefaultDay;
+
+use crate::{
+  gaps::{self, Gap},
+  i18n::{tr, trf, Key},
+  references::Kind,
+};
+
+use super::{toolbar::map_weekday_to_str, Component};
+
+const WORST_LIMIT: usize = 30;
+
+#[derive(Debug, Default)]
+pub struct GapsView {
+  gaps: Vec<Gap>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {}
+
+impl GapsView {
+  pub fn refresh(&mut self, days: &[DefaultDay]) {
+    self.gaps = gaps::report(days);
+  }
+
+  fn section(&self, kind: Kind) -> Element<Message> {
+    let found = self.gaps.iter().filter(|g| g.kind == kind).collect::<Vec<_>>();
+    let mut rows = found
+      .iter()
+      .take(WORST_LIMIT)
+      .map(|gap| {
+        row![
+          text(&gap.name).size(14).width(Length::Fixed(150.)),
+          text(map_weekday_to_str(gap.day)).size(14).width(Length::Fixed(40.)),
+          text(gap.to_string()).size(14),
+        ]
+        .spacing(10)
+        .into()
+      })
+      .collect::<Vec<_>>();
+    if found.len() > WORST_LIMIT {
+      rows.push(
+        text(trf(Key::AndMore, &[&(found.len() - WORST_LIMIT)]))
+          .size(14)
+          .into(),
+      );
+    }
+
+    column![
+      text(kind.to_string()).size(20),
+      text(gaps::summary(&self.gaps, kind)).size(14),
+      Rule::horizontal(1),
+      column(rows).spacing(2),
+    ]
+    .spacing(5)
+    .width(Length::FillPortion(1))
+    .into()
+  }
+}
+
+impl Component for GapsView {
+  type Message = Message;
+
+  fn update(&mut self, message: Self::Message) {
+    match message {}
+  }
+
+  fn view(&self) -> Element<Self::Message> {
+    let sections = row![self.section(Kind::Group), self.section(Kind::Teacher)].spacing(20);
+    let content = column![text(tr(Key::WorstGaps)), scrollable(sections)]
+      .spacing(10)
+      .padding([10, 15]);
+    container(content).width(Length::Fill).into()
+  }
+}
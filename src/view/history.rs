@@ -0,0 +1,134 @@
+use chrono::Weekday;
+use iced::{
+  theme::Button,
+  widget::{button, column, container, row, scrollable, text, Rule},
+  Element, Length,
+};
+use iced_aw::Icon;
+
+use crate::{
+  diff::{self, Change},
+  env::WEEKDAYS,
+  history::{self, Revision},
+  i18n::{tr, trf, Key},
+};
+
+use super::{basic_button, toolbar::map_weekday_to_str, with_icon, Component};
+
+#[derive(Debug)]
+pub struct HistoryView {
+  day: Weekday,
+  revisions: Vec<Revision>,
+  compared: Option<usize>,
+  changes: Vec<Change>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+  SelectDay(Weekday),
+  Compare(usize),
+  Restore(usize),
+}
+
+impl Default for HistoryView {
+  fn default() -> Self {
+    Self { day: Weekday::Mon, revisions: vec![], compared: None, changes: vec![] }
+  }
+}
+
+impl HistoryView {
+  pub fn refresh(&mut self) {
+    self.revisions = history::list(self.day);
+    self.compared = None;
+    self.changes.clear();
+  }
+
+  pub fn revision(&self, idx: usize) -> Option<&Revision> {
+    self.revisions.get(idx)
+  }
+
+  fn compare(&mut self, idx: usize) {
+    let Some(revision) = self.revisions.get(idx) else {
+      return;
+    };
+    match revision.load() {
+      Ok(old) => {
+        self.changes = diff::diff(&old, &history::current(self.day));
+        self.compared = Some(idx);
+      }
+      Err(err) => eprintln!("warn -> can't read revision {}: {}", revision.path.display(), err),
+    }
+  }
+}
+
+impl Component for HistoryView {
+  type Message = Message;
+
+  fn update(&mut self, message: Self::Message) {
+    match message {
+      Message::SelectDay(day) => {
+        self.day = day;
+        self.refresh();
+      }
+      Message::Compare(idx) => self.compare(idx),
+      Message::Restore(_) => (),
+    }
+  }
+
+  fn view(&self) -> Element<Self::Message> {
+    let days = row(
+      WEEKDAYS
+        .iter()
+        .map(|day| {
+          let style = if *day == self.day { Button::Primary } else { Button::Secondary };
+          button(text(map_weekday_to_str(*day)))
+            .on_press(Message::SelectDay(*day))
+            .padding(4)
+            .style(style)
+            .into()
+        })
+        .collect(),
+    )
+    .spacing(5);
+
+    let revisions = match self.revisions.is_empty() {
+      true => column![text(tr(Key::NoRevisions))],
+      false => column(
+        self
+          .revisions
+          .iter()
+          .enumerate()
+          .map(|(idx, revision)| {
+            row![
+              text(revision.saved_at.format("%d.%m.%Y %H:%M:%S").to_string()).width(Length::Fill),
+              basic_button(with_icon(tr(Key::Compare), Icon::Search), Message::Compare(idx)),
+              basic_button(with_icon(tr(Key::Restore), Icon::ArrowCounterclockwise), Message::Restore(idx)),
+            ]
+            .align_items(iced::Alignment::Center)
+            .spacing(10)
+            .into()
+          })
+          .collect(),
+      ),
+    }
+    .spacing(5);
+
+    let mut content = column![days, revisions].spacing(10).padding([10, 15]);
+    if let Some(revision) = self.compared.and_then(|idx| self.revisions.get(idx)) {
+      let changes = match self.changes.is_empty() {
+        true => vec![text(tr(Key::NoChanges)).size(14).into()],
+        false => self
+          .changes
+          .iter()
+          .map(|change| text(change.to_string()).size(14).into())
+          .collect::<Vec<Element<_>>>(),
+      };
+      content = content
+        .push(Rule::horizontal(1))
+        .push(text(trf(Key::ChangesSince, &[&revision.saved_at.format("%d.%m.%Y %H:%M:%S")])))
+        .push(column(changes).spacing(2));
+    }
+
+    container(scrollable(content)).width(Length::Fill).into()
+  }
+}
@@ -2,18 +2,43 @@ use std::fmt::Display;
 
 use iced::{
   theme::Button,
-  widget::{button, container, pick_list, row, text_input},
+  widget::{button, column, container, pick_list, row, text, text_input},
   Element, Length,
 };
 use iced_aw::Icon;
 use maiq_shared::default::{self, DefaultLesson};
 
-use super::{icon_button, Component};
+use crate::{
+  dictionary::{Dictionary, Field},
+  env,
+  i18n::{tr, trf, Key},
+  rotation::WeekPattern,
+};
+
+use super::{basic_button, icon_button, palette, Component};
+
+const SUGGESTIONS_LIMIT: usize = 5;
 
 pub trait LessonComponent {
   fn new(prev: Option<&DefaultLesson>) -> Self;
-  fn set_num(&mut self, num: String);
-  fn set_subgroup(&mut self, num: String);
+  fn set_num(&mut self, num: &str) -> Result<(), String>;
+  fn set_subgroup(&mut self, num: &str) -> Result<(), String>;
+  fn view_suggestions(&self, field: Field, dictionary: &Dictionary) -> Option<Element<Message>>;
+  fn view_with(&self, invalid: &[&InvalidInput], pattern: Option<&WeekPattern>, parity: bool) -> Element<Message>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ValidatedField {
+  Num,
+  Subgroup,
+  Pattern,
+}
+
+#[derive(Debug, Clone)]
+pub struct InvalidInput {
+  pub field: ValidatedField,
+  pub input: String,
+  pub error: String,
 }
 
 #[derive(Debug, Clone)]
@@ -24,40 +49,142 @@ pub enum Message {
   EditTeacher(String),
   EditClassroom(String),
   ForDaySelected(ForDay),
+  EditPattern(String),
+  Suggest(Field, String),
+  MoveUp,
+  MoveDown,
   Remove,
 }
 
+impl Message {
+  pub fn edited_field(&self) -> Option<Field> {
+    match self {
+      Message::EditName(_) => Some(Field::Subject),
+      Message::EditTeacher(_) => Some(Field::Teacher),
+      Message::EditClassroom(_) => Some(Field::Classroom),
+      _ => None,
+    }
+  }
+}
+
 impl LessonComponent for DefaultLesson {
   fn new(prev: Option<&DefaultLesson>) -> Self {
-    let num = prev.map(|l| if l.num > 9 { l.num } else { l.num + 1 }).unwrap_or(1);
+    let num = prev
+      .map(|l| if l.num >= env::max_lesson_num() { l.num } else { l.num + 1 })
+      .unwrap_or(1);
     Self { num, ..DefaultLesson::default() }
   }
 
-  fn set_num(&mut self, num: String) {
-    let num = num
-      .chars()
-      .last()
-      .filter(|c| c.is_ascii_digit())
-      .map(|c| c as u8 - b'0');
+  fn set_num(&mut self, num: &str) -> Result<(), String> {
+    let max = env::max_lesson_num();
+    match parse_number(num)? {
+      Some(x) if x > 0 && x <= max as u32 => self.num = x as u8,
+      Some(_) => return Err(trf(Key::NumRange, &[&max])),
+      None => return Err(tr(Key::NumRequired).into()),
+    }
+    Ok(())
+  }
 
-    match num {
-      Some(x) if x > 0 => self.num = x,
-      _ => (),
+  fn set_subgroup(&mut self, num: &str) -> Result<(), String> {
+    let max = env::max_subgroups();
+    match parse_number(num)? {
+      Some(x) if x > 0 && x <= max as u32 => self.subgroup = Some(x as u8),
+      Some(_) => return Err(trf(Key::SubgroupRange, &[&max])),
+      None => self.subgroup = None,
     }
+    Ok(())
   }
 
-  fn set_subgroup(&mut self, num: String) {
-    let num = num
-      .chars()
-      .last()
-      .filter(|c| c.is_ascii_digit())
-      .map(|c| c as u8 - b'0');
+  fn view_suggestions(&self, field: Field, dictionary: &Dictionary) -> Option<Element<Message>> {
+    let current = field.get(self);
+    let suggestions = dictionary
+      .suggest(field, current, SUGGESTIONS_LIMIT)
+      .into_iter()
+      .filter(|s| s != current)
+      .map(|s| basic_button(text(s.clone()), Message::Suggest(field, s)).into())
+      .collect::<Vec<Element<_>>>();
+
+    if suggestions.is_empty() {
+      return None;
+    }
 
-    match num {
-      Some(x) if x > 0 && x < 3 => self.subgroup = Some(x),
-      _ => self.subgroup = None,
+    Some(row(suggestions).spacing(5).padding([0, 0, 0, 15]).into())
+  }
+
+  fn view_with(&self, invalid: &[&InvalidInput], pattern: Option<&WeekPattern>, parity: bool) -> Element<Message> {
+    let input = |field: ValidatedField, value: String| {
+      invalid
+        .iter()
+        .find(|i| i.field == field)
+        .map(|i| i.input.clone())
+        .unwrap_or(value)
+    };
+    let num = input(ValidatedField::Num, self.num.to_string());
+    let subgroup = input(ValidatedField::Subgroup, self.subgroup.map(|sb| sb.to_string()).unwrap_or_default());
+
+    let for_day = match pattern {
+      Some(_) => ForDay::Custom,
+      None => self.is_even.into(),
+    };
+    let dropdown = container(pick_list(&DAYS[..], Some(for_day), Message::ForDaySelected)).width(Length::Fixed(110.0));
+    let pattern = pattern.map(|p| {
+      let value = input(ValidatedField::Pattern, p.to_string());
+      text_input(tr(Key::PatternPlaceholder), &value, Message::EditPattern).width(Length::Fixed(90.))
+    });
+    let mut inputs =
+      row![text_input("#", &num, Message::EditNum).width(30), text_input("&", &subgroup, Message::EditSubgroup).width(30)];
+    if parity {
+      inputs = inputs.push(dropdown);
+    }
+    if let Some(pattern) = pattern.filter(|_| parity) {
+      inputs = inputs.push(pattern);
     }
+    let inputs = inputs
+      .push(text_input(tr(Key::Subject), &self.name, Message::EditName).width(Length::FillPortion(7)))
+      .push(
+        text_input(tr(Key::Teacher), if let Some(teacher) = &self.teacher { teacher } else { "" }, Message::EditTeacher)
+          .width(Length::FillPortion(3)),
+      )
+      .push(
+        text_input(
+          tr(Key::ClassroomShort),
+          if let Some(classroom) = &self.classroom { classroom } else { "" },
+          Message::EditClassroom,
+        )
+        .width(Length::FillPortion(1)),
+      )
+      .push(icon_button(Icon::ArrowUp).on_press(Message::MoveUp))
+      .push(icon_button(Icon::ArrowDown).on_press(Message::MoveDown))
+      .push(
+        icon_button(Icon::Trash)
+          .on_press(Message::Remove)
+          .style(Button::Destructive),
+      )
+      .align_items(iced::Alignment::Center)
+      .padding([0, 0, 0, 15])
+      .spacing(10);
+
+    if invalid.is_empty() {
+      return inputs.into();
+    }
+
+    let errors = invalid
+      .iter()
+      .map(|i| i.error.as_str())
+      .collect::<Vec<_>>()
+      .join("; ");
+    column![inputs, text(errors).size(12).style(palette::error_text())]
+      .spacing(2)
+      .into()
+  }
+}
+
+fn parse_number(input: &str) -> Result<Option<u32>, String> {
+  let input = input.trim();
+  if input.is_empty() {
+    return Ok(None);
   }
+  input.parse().map(Some).map_err(|_| trf(Key::NotANumber, &[&input]))
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -66,6 +193,7 @@ pub enum ForDay {
   Every,
   Even,
   Odd,
+  Custom,
 }
 
 impl From<ForDay> for Option<bool> {
@@ -73,7 +201,7 @@ impl From<ForDay> for Option<bool> {
     match value {
       ForDay::Even => Some(true),
       ForDay::Odd => Some(false),
-      ForDay::Every => None,
+      ForDay::Every | ForDay::Custom => None,
     }
   }
 }
@@ -94,50 +222,34 @@ impl Display for ForDay {
       f,
       "{}",
       match self {
-        ForDay::Every => "Всегда",
-        ForDay::Even => "Чётная",
-        ForDay::Odd => "Нечётная",
+        ForDay::Every => tr(Key::Every),
+        ForDay::Even => tr(Key::Even),
+        ForDay::Odd => tr(Key::Odd),
+        ForDay::Custom => tr(Key::Custom),
       }
     )
   }
 }
 
-const DAYS: [ForDay; 3] = [ForDay::Every, ForDay::Even, ForDay::Odd];
+const DAYS: [ForDay; 4] = [ForDay::Every, ForDay::Even, ForDay::Odd, ForDay::Custom];
 
 impl Component for DefaultLesson {
   type Message = Message;
 
   fn update(&mut self, message: Self::Message) {
     match message {
-      Message::EditNum(n) => self.set_num(n),
-      Message::EditSubgroup(sb) => self.set_subgroup(sb),
+      Message::EditNum(n) => self.set_num(&n).unwrap_or_default(),
+      Message::EditSubgroup(sb) => self.set_subgroup(&sb).unwrap_or_default(),
       Message::EditName(x) => self.name = x,
       Message::EditTeacher(x) => self.teacher = Some(x),
       Message::EditClassroom(x) => self.classroom = Some(x),
       Message::ForDaySelected(x) => self.is_even = x.into(),
+      Message::Suggest(field, x) => field.set(self, x),
       _ => (),
     }
   }
 
   fn view(&self) -> Element<Self::Message> {
-    let dropdown = container(pick_list(&DAYS[..], Some(Into::<ForDay>::into(self.is_even)), Message::ForDaySelected))
-      .width(Length::Fixed(110.0));
-    row![
-      text_input("#", &self.num.to_string(), Message::EditNum).width(20),
-      text_input("&", &self.subgroup.map(|sb| sb.to_string()).unwrap_or_default(), Message::EditSubgroup).width(20),
-      dropdown,
-      text_input("Предмет", &self.name, Message::EditName).width(Length::FillPortion(7)),
-      text_input("Преподаватель", if let Some(teacher) = &self.teacher { teacher } else { "" }, Message::EditTeacher)
-        .width(Length::FillPortion(3)),
-      text_input("Ауд.", if let Some(classroom) = &self.classroom { classroom } else { "" }, Message::EditClassroom)
-        .width(Length::FillPortion(1)),
-      icon_button(Icon::Trash)
-        .on_press(Message::Remove)
-        .style(Button::Destructive)
-    ]
-    .align_items(iced::Alignment::Center)
-    .padding([0, 0, 0, 15])
-    .spacing(10)
-    .into()
+    self.view_with(&[], None, true)
   }
 }
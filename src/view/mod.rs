@@ -5,15 +5,44 @@ use iced::{
 };
 use iced_aw::{Icon, ICON_FONT};
 
+pub mod changes;
+pub mod cheat_sheet;
+pub mod curriculum;
 pub mod default_group;
 pub mod editor;
+pub mod final_day;
+pub mod gaps;
+pub mod history;
 pub mod lesson;
 pub mod notification;
+pub mod palette;
+pub mod recovery;
+pub mod references;
+pub mod rooms;
+pub mod search;
+pub mod settings;
+pub mod slots;
+pub mod stats;
+pub mod substitution;
+pub mod teachers;
 pub mod toolbar;
 
 pub type GroupMessage = default_group::Message;
 pub type LessonMessage = lesson::Message;
 pub type EditorMessage = editor::Message;
+pub type ReferencesMessage = references::Message;
+pub type SearchMessage = search::Message;
+pub type SettingsMessage = settings::Message;
+pub type HistoryMessage = history::Message;
+pub type ChangesMessage = changes::Message;
+pub type FinalDayMessage = final_day::Message;
+pub type TeachersMessage = teachers::Message;
+pub type RoomsMessage = rooms::Message;
+pub type SlotsMessage = slots::Message;
+pub type SubstitutionMessage = substitution::Message;
+pub type StatsMessage = stats::Message;
+pub type GapsMessage = gaps::Message;
+pub type CurriculumMessage = curriculum::Message;
 
 pub trait Component {
   type Message;
@@ -1,24 +1,40 @@
-use super::{icon_button, Component};
+use std::time::Duration;
+
+use super::{icon_button, palette, Component};
 use iced::{
   theme::{Button, Container, Text},
+  time::Instant,
   widget::{column, container, row, text},
-  Color,
 };
 use iced_aw::Icon;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+  Ok,
+  Error,
+}
+
+const INFO_TTL: Duration = Duration::from_secs(5);
+
 pub struct Notification {
   pub header: String,
-  pub color: Color,
+  pub level: Level,
   pub body: String,
+  created: Instant,
 }
 
 impl Notification {
   pub fn error(header: impl Into<String>, body: impl Into<String>) -> Self {
-    Self { header: header.into(), body: body.into(), color: Color::from_rgb8(240, 0, 0) }
+    Self { header: header.into(), body: body.into(), level: Level::Error, created: Instant::now() }
   }
 
   pub fn ok(header: impl Into<String>, body: impl Into<String>) -> Self {
-    Self { header: header.into(), body: body.into(), color: Color::from_rgb8(0, 0, 0) }
+    Self { header: header.into(), body: body.into(), level: Level::Ok, created: Instant::now() }
+  }
+
+  // Errors stay until closed, info goes away by itself
+  pub fn expired(&self, now: Instant) -> bool {
+    self.level == Level::Ok && now.duration_since(self.created) > INFO_TTL
   }
 }
 
@@ -28,8 +44,13 @@ impl Component for Notification {
   fn update(&mut self, _: Self::Message) {}
 
   fn view(&self) -> iced::Element<Self::Message> {
+    let palette = palette::current();
+    let color = match self.level {
+      Level::Ok => palette.primary,
+      Level::Error => palette.danger,
+    };
     let row = row![
-      column![text(&self.header).size(14).style(Text::Color(self.color)), text(&self.body)].padding([0, 7]),
+      column![text(&self.header).size(14).style(Text::Color(color)), text(&self.body)].padding([0, 7]),
       icon_button(Icon::Trash).on_press(()).style(Button::Destructive)
     ]
     .padding(5)
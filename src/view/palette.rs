@@ -0,0 +1,45 @@
+use std::sync::RwLock;
+
+use iced::{theme, theme::Palette, Color, Theme};
+
+use crate::settings::AppTheme;
+
+pub const LIGHT: Palette = Palette {
+  background: Color::from_rgb(0.98, 0.98, 0.98),
+  text: Color::from_rgb(0.11, 0.11, 0.13),
+  primary: Color::from_rgb(0.22, 0.45, 0.85),
+  success: Color::from_rgb(0.18, 0.58, 0.34),
+  danger: Color::from_rgb(0.84, 0.19, 0.19),
+};
+
+pub const DARK: Palette = Palette {
+  background: Color::from_rgb(0.12, 0.12, 0.14),
+  text: Color::from_rgb(0.9, 0.9, 0.92),
+  primary: Color::from_rgb(0.42, 0.6, 0.95),
+  success: Color::from_rgb(0.35, 0.75, 0.5),
+  danger: Color::from_rgb(0.95, 0.42, 0.42),
+};
+
+// Widgets without access to the `Theme` (notification headers, error texts) read the palette from here
+static CURRENT: RwLock<Palette> = RwLock::new(LIGHT);
+
+pub fn apply(theme: AppTheme) -> Theme {
+  let palette = match theme {
+    AppTheme::Light => LIGHT,
+    AppTheme::Dark => DARK,
+    AppTheme::System => match dark_light::detect() {
+      dark_light::Mode::Dark => DARK,
+      _ => LIGHT,
+    },
+  };
+  *CURRENT.write().unwrap() = palette;
+  Theme::custom(palette)
+}
+
+pub fn current() -> Palette {
+  *CURRENT.read().unwrap()
+}
+
+pub fn error_text() -> theme::Text {
+  theme::Text::Color(current().danger)
+}
@@ -0,0 +1,44 @@
+use iced::{
+  widget::{column, row, scrollable, text},
+  Length,
+};
+use iced_aw::{Card, Icon};
+
+use crate::{
+  app::AppMessage,
+  diff::Change,
+  i18n::{tr, trf, Key},
+  recovery::Recovery,
+};
+
+use super::{basic_button, with_icon};
+
+type Element<'a> = iced::Element<'a, AppMessage, iced::Renderer>;
+
+pub fn recovery_prompt<'a>(recovery: &'a Recovery, changes: &'a [Change]) -> Element<'a> {
+  let source = match &recovery.source {
+    Some(path) => trf(Key::OriginalFile, &[path]),
+    None => tr(Key::NewFile).into(),
+  };
+  let changes = changes
+    .iter()
+    .map(|change| text(change.to_string()).size(14).into())
+    .collect::<Vec<Element>>();
+
+  let body = column![
+    text(trf(Key::AutosavedAt, &[&recovery.saved_at.format("%d.%m.%Y %H:%M")])),
+    text(source).size(14),
+    scrollable(column(changes).spacing(2)).height(Length::Fixed(300.)),
+  ]
+  .spacing(10);
+  let foot = row![
+    basic_button(with_icon(tr(Key::Restore), Icon::ArrowCounterclockwise), AppMessage::Recover(true)),
+    basic_button(with_icon(tr(Key::Discard), Icon::Trash), AppMessage::Recover(false)),
+  ]
+  .spacing(10);
+
+  Card::new(with_icon(tr(Key::RecoveryFound), Icon::ExclamationTriangle), body)
+    .foot(foot)
+    .max_width(600.)
+    .into()
+}
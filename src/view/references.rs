@@ -0,0 +1,171 @@
+use iced::{
+  theme::Button,
+  widget::{column, container, pick_list, row, scrollable, text, text_input, Rule},
+  Element, Length,
+};
+use iced_aw::Icon;
+use maiq_shared::default::DefaultDay;
+
+use crate::{
+  i18n::{tr, Key},
+  references::{Kind, Mismatch, References},
+};
+
+use super::{basic_button, icon_button, with_icon, Component};
+
+#[derive(Debug, Default)]
+pub struct ReferencesEditor {
+  references: References,
+  kind: Kind,
+  drafts: Vec<String>,
+  new_entry: String,
+  mismatches: Vec<Mismatch>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+  SelectKind(Kind),
+  EditDraft(usize, String),
+  EditNew(String),
+  Add,
+  AddMismatch(Kind, String),
+  Remove(usize),
+  Rename(usize),
+  Save,
+}
+
+impl ReferencesEditor {
+  pub fn new(references: References) -> Self {
+    let mut editor = Self { references, ..Self::default() };
+    editor.reset_drafts();
+    editor
+  }
+
+  pub fn references(&self) -> &References {
+    &self.references
+  }
+
+  pub fn refresh(&mut self, days: &[DefaultDay]) {
+    self.mismatches = self.references.mismatches(days);
+  }
+
+  pub fn rename_target(&self, idx: usize) -> Option<(Kind, String, String)> {
+    let from = self.references.entries(self.kind).get(idx)?;
+    let to = self.drafts.get(idx)?.trim();
+    (!to.is_empty() && from != to).then(|| (self.kind, from.clone(), to.to_string()))
+  }
+
+  pub fn apply_rename(&mut self, idx: usize) {
+    if let Some((kind, _, to)) = self.rename_target(idx) {
+      self.references.entries_mut(kind).remove(idx);
+      self.references.add(kind, &to);
+      self.reset_drafts();
+    }
+  }
+
+  fn reset_drafts(&mut self) {
+    self.drafts = self.references.entries(self.kind).clone();
+  }
+}
+
+impl Component for ReferencesEditor {
+  type Message = Message;
+
+  fn update(&mut self, message: Self::Message) {
+    match message {
+      Message::SelectKind(kind) => {
+        self.kind = kind;
+        self.reset_drafts();
+      }
+      Message::EditDraft(idx, value) => {
+        if let Some(draft) = self.drafts.get_mut(idx) {
+          *draft = value
+        }
+      }
+      Message::EditNew(value) => self.new_entry = value,
+      Message::Add => {
+        if self.references.add(self.kind, &self.new_entry) {
+          self.new_entry.clear();
+          self.reset_drafts();
+        }
+      }
+      Message::AddMismatch(kind, value) => {
+        self.references.add(kind, &value);
+        self.reset_drafts();
+      }
+      Message::Remove(idx) => {
+        self.references.entries_mut(self.kind).remove(idx);
+        self.reset_drafts();
+      }
+      Message::Rename(idx) => self.apply_rename(idx),
+      Message::Save => (),
+    }
+  }
+
+  fn view(&self) -> Element<Self::Message> {
+    let header = row![
+      pick_list(&Kind::ALL[..], Some(self.kind), Message::SelectKind),
+      basic_button(with_icon(tr(Key::Save), Icon::Save), Message::Save),
+    ]
+    .align_items(iced::Alignment::Center)
+    .spacing(10);
+
+    let entries = column(
+      self
+        .drafts
+        .iter()
+        .enumerate()
+        .map(|(idx, draft)| {
+          let mut rename = icon_button(Icon::PencilSquare);
+          if self.rename_target(idx).is_some() {
+            rename = rename.on_press(Message::Rename(idx));
+          }
+          row![
+            text_input(tr(Key::Value), draft, move |v| Message::EditDraft(idx, v)).width(Length::Fill),
+            rename,
+            icon_button(Icon::Trash)
+              .on_press(Message::Remove(idx))
+              .style(Button::Destructive),
+          ]
+          .align_items(iced::Alignment::Center)
+          .spacing(10)
+          .into()
+        })
+        .collect(),
+    )
+    .spacing(5);
+
+    let new_entry = row![
+      text_input(tr(Key::NewValue), &self.new_entry, Message::EditNew)
+        .on_submit(Message::Add)
+        .width(Length::Fill),
+      icon_button(Icon::Plus).on_press(Message::Add),
+    ]
+    .align_items(iced::Alignment::Center)
+    .spacing(10);
+
+    let mismatches = column(
+      self
+        .mismatches
+        .iter()
+        .filter(|m| m.kind == self.kind)
+        .map(|m| {
+          row![
+            text(format!("{} ({})", m.value, m.occurrences)).width(Length::Fill),
+            icon_button(Icon::Plus).on_press(Message::AddMismatch(m.kind, m.value.clone())),
+          ]
+          .align_items(iced::Alignment::Center)
+          .spacing(10)
+          .into()
+        })
+        .collect(),
+    )
+    .spacing(5);
+
+    let content = column![header, new_entry, entries, Rule::horizontal(1), text(tr(Key::NotInReferences)).size(14), mismatches]
+      .spacing(10)
+      .padding([10, 15]);
+
+    container(scrollable(content)).width(Length::Fill).into()
+  }
+}
@@ -0,0 +1,141 @@
+use chrono::Weekday;
+use iced::{
+  theme::{self, Button},
+  widget::{button, column, container, pick_list, row, scrollable, text, text_input, Rule},
+  Element, Length,
+};
+use maiq_shared::default::DefaultDay;
+
+use crate::{
+  env::{self, WEEKDAYS},
+  i18n::{tr, trf, Key},
+  occupancy::{self, Grid, WeekParity},
+};
+
+use super::{palette, toolbar::map_weekday_to_str, Component};
+
+#[derive(Debug)]
+pub struct RoomsView {
+  day: Weekday,
+  parity: WeekParity,
+  grid: Grid,
+  classrooms: Vec<String>,
+  num: String,
+  free: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+  SelectDay(Weekday),
+  SelectParity(WeekParity),
+  EditNum(String),
+}
+
+impl Default for RoomsView {
+  fn default() -> Self {
+    Self {
+      day: Weekday::Mon,
+      parity: WeekParity::default(),
+      grid: Grid::new(),
+      classrooms: vec![],
+      num: String::new(),
+      free: None,
+    }
+  }
+}
+
+impl RoomsView {
+  pub fn refresh(&mut self, days: &[DefaultDay], known: &[String]) {
+    self.grid = days
+      .iter()
+      .find(|d| d.day == self.day)
+      .map(|d| occupancy::grid(d, self.parity))
+      .unwrap_or_default();
+    self.classrooms = occupancy::classrooms(days, known);
+    self.free = self
+      .num
+      .trim()
+      .parse()
+      .ok()
+      .map(|num| occupancy::free_rooms(days, known, self.day, num, self.parity));
+  }
+
+  fn grid_view(&self) -> Element<Message> {
+    const CELL: Length = Length::Fixed(90.);
+    let nums = 1..=env::max_lesson_num();
+
+    let mut header = row![text(tr(Key::ClassroomShort)).width(CELL)].spacing(5);
+    for num in nums.clone() {
+      header = header.push(text(num).width(CELL));
+    }
+
+    let rows = self
+      .classrooms
+      .iter()
+      .map(|classroom| {
+        let lessons = self.grid.get(classroom);
+        let mut line = row![text(classroom).width(CELL)].spacing(5);
+        for num in nums.clone() {
+          let occupants = lessons.and_then(|l| l.get(&num));
+          let cell = match occupants {
+            None => text("—"),
+            Some(occupants) => {
+              let label = occupants.iter().map(|o| o.to_string()).collect::<Vec<_>>().join(", ");
+              match occupants.len() > 1 {
+                true => text(label).style(theme::Text::Color(palette::current().danger)),
+                false => text(label),
+              }
+            }
+          };
+          line = line.push(cell.size(14).width(CELL));
+        }
+        line.into()
+      })
+      .collect();
+
+    column![header, Rule::horizontal(1), column(rows).spacing(3)]
+      .spacing(5)
+      .into()
+  }
+}
+
+impl Component for RoomsView {
+  type Message = Message;
+
+  fn update(&mut self, message: Self::Message) {
+    match message {
+      Message::SelectDay(day) => self.day = day,
+      Message::SelectParity(parity) => self.parity = parity,
+      Message::EditNum(num) => self.num = num,
+    }
+  }
+
+  fn view(&self) -> Element<Self::Message> {
+    let mut header = row![].spacing(5).align_items(iced::Alignment::Center);
+    for day in WEEKDAYS {
+      let style = if day == self.day { Button::Primary } else { Button::Secondary };
+      header = header.push(
+        button(text(map_weekday_to_str(day)))
+          .on_press(Message::SelectDay(day))
+          .padding(4)
+          .style(style),
+      );
+    }
+    header = header.push(pick_list(&WeekParity::ALL[..], Some(self.parity), Message::SelectParity));
+
+    let finder =
+      row![text(tr(Key::FreeRoomsAt)), text_input(tr(Key::Num), &self.num, Message::EditNum).width(Length::Fixed(60.))]
+        .align_items(iced::Alignment::Center)
+        .spacing(10);
+    let free = match &self.free {
+      None => text(""),
+      Some(free) if free.is_empty() => text(tr(Key::NoFreeRooms)),
+      Some(free) => text(trf(Key::FreeRooms, &[&free.len(), &free.join(", ")])),
+    };
+
+    let content = column![header, finder, free.size(14), Rule::horizontal(1), self.grid_view()]
+      .spacing(10)
+      .padding([10, 15]);
+    container(scrollable(content)).width(Length::Fill).into()
+  }
+}
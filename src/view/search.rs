@@ -0,0 +1,107 @@
+use iced::{
+  widget::{column, pick_list, row, text, text_input, toggler},
+  Element, Length,
+};
+use iced_aw::Icon;
+
+use crate::{
+  i18n::{tr, trf, Key},
+  search::{Hit, Scope},
+};
+
+use super::{basic_button, toolbar::map_weekday_to_str, with_icon, Component};
+
+const PREVIEW_LIMIT: usize = 50;
+
+#[derive(Debug, Default)]
+pub struct SearchBar {
+  query: String,
+  replacement: String,
+  replace_mode: bool,
+  scope: Scope,
+  preview: Vec<Hit>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+  EditQuery(String),
+  EditReplacement(String),
+  ToggleReplace(bool),
+  SelectScope(Scope),
+  Replace,
+}
+
+impl SearchBar {
+  pub fn query(&self) -> &str {
+    &self.query
+  }
+
+  pub fn replacement(&self) -> Option<(&str, &str, Scope)> {
+    (self.replace_mode && !self.query.is_empty()).then_some((self.query.as_str(), self.replacement.as_str(), self.scope))
+  }
+
+  pub fn set_preview(&mut self, preview: Vec<Hit>) {
+    self.preview = preview;
+  }
+}
+
+impl Component for SearchBar {
+  type Message = Message;
+
+  fn update(&mut self, message: Self::Message) {
+    match message {
+      Message::EditQuery(query) => self.query = query,
+      Message::EditReplacement(replacement) => self.replacement = replacement,
+      Message::ToggleReplace(enabled) => self.replace_mode = enabled,
+      Message::SelectScope(scope) => self.scope = scope,
+      Message::Replace => self.preview.clear(),
+    }
+  }
+
+  fn view(&self) -> Element<Self::Message> {
+    let mut bar = row![
+      with_icon(text_input(tr(Key::Search), &self.query, Message::EditQuery).width(Length::Fixed(250.)), Icon::Search),
+      toggler(Some(tr(Key::ReplaceMode).into()), self.replace_mode, Message::ToggleReplace).width(Length::Shrink),
+    ]
+    .align_items(iced::Alignment::Center)
+    .spacing(15)
+    .padding([0, 15]);
+
+    if !self.replace_mode {
+      return bar.into();
+    }
+
+    bar = bar
+      .push(text_input(tr(Key::ReplaceWith), &self.replacement, Message::EditReplacement).width(Length::Fixed(250.)))
+      .push(pick_list(&Scope::ALL[..], Some(self.scope), Message::SelectScope));
+    if !self.preview.is_empty() {
+      bar = bar.push(basic_button(with_icon(tr(Key::ReplaceAction), Icon::ArrowRepeat), Message::Replace));
+    }
+
+    let mut rows = self
+      .preview
+      .iter()
+      .take(PREVIEW_LIMIT)
+      .map(|hit| {
+        let place = match hit.num {
+          Some(num) => trf(Key::HitLesson, &[&map_weekday_to_str(hit.day), &hit.group, &num]),
+          None => format!("{}, {}", map_weekday_to_str(hit.day), hit.group),
+        };
+        text(format!("{}: {} → {}", place, hit.before, hit.after))
+          .size(14)
+          .into()
+      })
+      .collect::<Vec<Element<_>>>();
+    if self.preview.len() > PREVIEW_LIMIT {
+      rows.push(
+        text(trf(Key::AndMore, &[&(self.preview.len() - PREVIEW_LIMIT)]))
+          .size(14)
+          .into(),
+      );
+    }
+
+    column![bar, column(rows).spacing(2).padding([0, 15])]
+      .spacing(10)
+      .into()
+  }
+}
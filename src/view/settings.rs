@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use iced::{
+  widget::{column, container, pick_list, row, scrollable, text, text_input},
+  Element, Length,
+};
+use iced_aw::Icon;
+
+use crate::{
+  i18n::{tr, trf, Key},
+  settings::{self, AppTheme, Language, Settings, SettingsField},
+};
+
+use super::{basic_button, palette, with_icon, Component};
+
+#[derive(Debug, Default)]
+pub struct SettingsEditor {
+  drafts: BTreeMap<SettingsField, String>,
+  theme: AppTheme,
+  language: Language,
+  errors: BTreeMap<SettingsField, String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+  Edit(SettingsField, String),
+  SelectTheme(AppTheme),
+  SelectLanguage(Language),
+  Reset,
+  Save,
+}
+
+impl SettingsEditor {
+  // Drafts are what the settings file holds; overrides from the environment are listed separately
+  pub fn new() -> Self {
+    let mut editor = Self::default();
+    editor.reset(&Settings::from_file());
+    editor
+  }
+
+  // Parses the drafts and validates them; errors are kept to be shown under the fields
+  pub fn settings(&mut self) -> Option<Settings> {
+    let mut errors = BTreeMap::new();
+    let mut number = |field, min| match self.draft(field).trim().parse::<u8>() {
+      Ok(value) => value,
+      Err(_) => {
+        errors.insert(field, tr(Key::ExpectedNumber).to_string());
+        min
+      }
+    };
+    let max_lesson_num = number(SettingsField::MaxLessonNum, 1);
+    let max_subgroups = number(SettingsField::MaxSubgroups, 1);
+    let mut date = |field, fallback| match NaiveDate::parse_from_str(self.draft(field).trim(), "%Y-%m-%d") {
+      Ok(date) => date,
+      Err(_) => {
+        errors.insert(field, tr(Key::DateFormat).to_string());
+        fallback
+      }
+    };
+    let defaults = Settings::default();
+    let parity_anchor = date(SettingsField::ParityAnchor, defaults.parity_anchor);
+    let semester_start = date(SettingsField::SemesterStart, defaults.semester_start);
+    let semester_end = date(SettingsField::SemesterEnd, defaults.semester_end);
+
+    let settings = Settings {
+      export_dir: self.draft(SettingsField::ExportDir).trim().to_string(),
+      defaults_dir: self.draft(SettingsField::DefaultsDir).trim().to_string(),
+      server_url: self.draft(SettingsField::ServerUrl).trim().to_string(),
+      theme: self.theme,
+      language: self.language,
+      parity_anchor,
+      semester_start,
+      semester_end,
+      max_lesson_num,
+      max_subgroups,
+    };
+    for (field, error) in settings.validate() {
+      errors.entry(field).or_insert(error);
+    }
+
+    self.errors = errors;
+    self.errors.is_empty().then_some(settings)
+  }
+
+  fn reset(&mut self, settings: &Settings) {
+    self.drafts = BTreeMap::from([
+      (SettingsField::ExportDir, settings.export_dir.clone()),
+      (SettingsField::DefaultsDir, settings.defaults_dir.clone()),
+      (SettingsField::ServerUrl, settings.server_url.clone()),
+      (SettingsField::ParityAnchor, settings.parity_anchor.format("%Y-%m-%d").to_string()),
+      (SettingsField::SemesterStart, settings.semester_start.format("%Y-%m-%d").to_string()),
+      (SettingsField::SemesterEnd, settings.semester_end.format("%Y-%m-%d").to_string()),
+      (SettingsField::MaxLessonNum, settings.max_lesson_num.to_string()),
+      (SettingsField::MaxSubgroups, settings.max_subgroups.to_string()),
+    ]);
+    self.theme = settings.theme;
+    self.language = settings.language;
+    self.errors.clear();
+  }
+
+  fn draft(&self, field: SettingsField) -> &str {
+    self.drafts.get(&field).map(|d| d.as_str()).unwrap_or_default()
+  }
+
+  fn field_view<'a>(&'a self, label: &str, placeholder: &str, field: SettingsField) -> Element<'a, Message> {
+    let input = row![
+      text(label).width(Length::Fixed(220.)),
+      text_input(placeholder, self.draft(field), move |v| Message::Edit(field, v)).width(Length::Fixed(300.)),
+    ]
+    .align_items(iced::Alignment::Center)
+    .spacing(10);
+
+    match self.errors.get(&field) {
+      Some(error) => column![input, text(error).size(14).style(palette::error_text())]
+        .spacing(2)
+        .into(),
+      None => input.into(),
+    }
+  }
+}
+
+impl Component for SettingsEditor {
+  type Message = Message;
+
+  fn update(&mut self, message: Self::Message) {
+    match message {
+      Message::Edit(field, value) => {
+        self.errors.remove(&field);
+        self.drafts.insert(field, value);
+      }
+      Message::SelectTheme(theme) => self.theme = theme,
+      Message::SelectLanguage(language) => self.language = language,
+      Message::Reset => self.reset(&Settings::from_file()),
+      Message::Save => (),
+    }
+  }
+
+  fn view(&self) -> Element<Self::Message> {
+    let header = row![
+      basic_button(with_icon(tr(Key::Save), Icon::Save), Message::Save),
+      basic_button(with_icon(tr(Key::Reset), Icon::ArrowCounterclockwise), Message::Reset),
+    ]
+    .spacing(10);
+
+    let pickers = column![
+      row![
+        text(tr(Key::Theme)).width(Length::Fixed(220.)),
+        pick_list(&AppTheme::ALL[..], Some(self.theme), Message::SelectTheme)
+      ]
+      .align_items(iced::Alignment::Center)
+      .spacing(10),
+      row![
+        text(tr(Key::Language)).width(Length::Fixed(220.)),
+        pick_list(&Language::ALL[..], Some(self.language), Message::SelectLanguage)
+      ]
+      .align_items(iced::Alignment::Center)
+      .spacing(10),
+    ]
+    .spacing(10);
+
+    let mut content = column![
+      header,
+      self.field_view(tr(Key::ExportDir), "export", SettingsField::ExportDir),
+      self.field_view(tr(Key::DefaultsDir), "default", SettingsField::DefaultsDir),
+      self.field_view(tr(Key::ServerUrl), "https://", SettingsField::ServerUrl),
+      self.field_view(tr(Key::ParityAnchor), tr(Key::DatePlaceholder), SettingsField::ParityAnchor),
+      self.field_view(tr(Key::SemesterStart), tr(Key::DatePlaceholder), SettingsField::SemesterStart),
+      self.field_view(tr(Key::SemesterEnd), tr(Key::DatePlaceholder), SettingsField::SemesterEnd),
+      self.field_view(tr(Key::MaxLessonNum), "10", SettingsField::MaxLessonNum),
+      self.field_view(tr(Key::MaxSubgroups), "2", SettingsField::MaxSubgroups),
+      pickers,
+      text(trf(Key::SettingsFile, &[&settings::config_path().display()])).size(14),
+    ]
+    .spacing(10)
+    .padding([10, 15]);
+
+    let overridden = settings::overridden();
+    if !overridden.is_empty() {
+      content = content.push(text(trf(Key::OverriddenByEnv, &[&overridden.join(", ")])).size(14));
+    }
+
+    container(scrollable(content)).width(Length::Fill).into()
+  }
+}
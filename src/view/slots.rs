@@ -0,0 +1,124 @@
+use iced::{
+  widget::{column, container, row, scrollable, text, Rule},
+  Element, Length,
+};
+use iced_aw::Icon;
+use maiq_shared::default::DefaultLesson;
+
+use crate::{
+  diff, env,
+  i18n::{tr, trf, Key},
+  pivot,
+  slots::Slot,
+};
+
+use super::{basic_button, toolbar::map_weekday_to_str, with_icon, Component};
+
+// Where the lesson under the editor's cursor could be moved
+#[derive(Debug, Default)]
+pub struct SlotsView {
+  target: Option<Target>,
+  slots: Vec<Slot>,
+  // A move to another day writes the saved defaults, so it waits for a confirmation
+  confirm: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Target {
+  pub group: usize,
+  pub lesson: usize,
+  pub name: String,
+  pub original: DefaultLesson,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+  Apply(usize),
+  Confirm(usize),
+  Cancel,
+}
+
+impl SlotsView {
+  pub fn set(&mut self, target: Target, slots: Vec<Slot>) {
+    self.target = Some(target);
+    self.slots = slots;
+    self.confirm = None;
+  }
+
+  pub fn clear(&mut self) {
+    self.target = None;
+    self.slots.clear();
+    self.confirm = None;
+  }
+
+  pub fn ask(&mut self, idx: usize) {
+    self.confirm = Some(idx);
+  }
+
+  pub fn target(&self) -> Option<&Target> {
+    self.target.as_ref()
+  }
+
+  pub fn slot(&self, idx: usize) -> Option<&Slot> {
+    self.slots.get(idx)
+  }
+}
+
+impl Component for SlotsView {
+  type Message = Message;
+
+  fn update(&mut self, message: Self::Message) {
+    match message {
+      Message::Cancel => self.confirm = None,
+      Message::Apply(_) | Message::Confirm(_) => (),
+    }
+  }
+
+  fn view(&self) -> Element<Self::Message> {
+    let Some(target) = &self.target else {
+      return container(text(tr(Key::NoSlotTarget))).padding([10, 15]).into();
+    };
+
+    let header = text(trf(Key::SlotsFor, &[&target.name, &diff::describe(&target.original)]));
+    let slots = match self.slots.is_empty() {
+      true => column![text(tr(Key::NoSlots))],
+      false => column(
+        self
+          .slots
+          .iter()
+          .enumerate()
+          .map(|(idx, slot)| {
+            row![
+              text(map_weekday_to_str(slot.day)).width(Length::Fixed(120.)),
+              text(slot.num).width(Length::Fixed(40.)),
+              text(pivot::parity(slot.is_even)).width(Length::Fixed(90.)),
+              text(slot.classroom.as_deref().unwrap_or("—")).width(Length::Fixed(80.)),
+              text(trf(Key::NewGaps, &[&slot.new_gaps])).width(Length::Fill),
+              basic_button(with_icon(tr(Key::MoveHere), Icon::ArrowRight), Message::Apply(idx)),
+            ]
+            .align_items(iced::Alignment::Center)
+            .spacing(10)
+            .into()
+          })
+          .collect(),
+      ),
+    }
+    .spacing(5);
+
+    let mut content = column![header, Rule::horizontal(1)].spacing(10).padding([10, 15]);
+    if let Some((idx, slot)) = self.confirm.and_then(|idx| Some((idx, self.slots.get(idx)?))) {
+      let question = trf(Key::ConfirmMoveToDay, &[&map_weekday_to_str(slot.day), &env::default_path(slot.day)]);
+      content = content.push(
+        row![
+          text(question).width(Length::Fill),
+          basic_button(with_icon(tr(Key::MoveHere), Icon::Check), Message::Confirm(idx)),
+          basic_button(with_icon(tr(Key::Cancel), Icon::X), Message::Cancel),
+        ]
+        .align_items(iced::Alignment::Center)
+        .spacing(10),
+      );
+    }
+    let content = content.push(scrollable(slots));
+    container(content).width(Length::Fill).into()
+  }
+}
@@ -0,0 +1,100 @@
+use iced::{
+  widget::{column, container, row, scrollable, text, Rule},
+  Element, Length,
+};
+use iced_aw::Icon;
+use maiq_shared::default::DefaultDay;
+
+use crate::{
+  i18n::{tr, Key},
+  stats::{self, Stats},
+};
+
+use super::{basic_button, toolbar::map_weekday_to_str, with_icon, Component};
+
+#[derive(Debug, Default)]
+pub struct StatsView {
+  stats: Stats,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+  Export,
+}
+
+impl StatsView {
+  pub fn refresh(&mut self, days: &[DefaultDay]) {
+    self.stats = stats::compute(days);
+  }
+
+  pub fn stats(&self) -> &Stats {
+    &self.stats
+  }
+
+  fn table<'a>(title: &str, rows: impl Iterator<Item = (String, String)>) -> Element<'a, Message> {
+    let rows = rows
+      .map(|(name, value)| {
+        row![text(name).size(14).width(Length::Fill), text(value).size(14)]
+          .spacing(10)
+          .into()
+      })
+      .collect();
+    column![text(title).size(20), Rule::horizontal(1), column(rows).spacing(2)]
+      .spacing(5)
+      .width(Length::FillPortion(1))
+      .into()
+  }
+}
+
+impl Component for StatsView {
+  type Message = Message;
+
+  fn update(&mut self, message: Self::Message) {
+    match message {
+      Message::Export => (),
+    }
+  }
+
+  fn view(&self) -> Element<Self::Message> {
+    let loads = |rows: &[(String, f32)]| {
+      rows
+        .iter()
+        .map(|(name, load)| (name.clone(), format!("{:.1}", load)))
+        .collect::<Vec<_>>()
+        .into_iter()
+    };
+
+    let header = row![
+      text(tr(Key::LessonsPerWeek)).width(Length::Fill),
+      basic_button(with_icon(tr(Key::Export), Icon::Upload), Message::Export),
+    ]
+    .align_items(iced::Alignment::Center)
+    .spacing(10);
+
+    let tables = row![
+      Self::table(tr(Key::Teachers), loads(&self.stats.teachers)),
+      Self::table(tr(Key::Groups), loads(&self.stats.groups)),
+      Self::table(tr(Key::Subjects), loads(&self.stats.subjects)),
+      Self::table(
+        tr(Key::ClassroomUtilization),
+        self
+          .stats
+          .classrooms
+          .iter()
+          .map(|(name, percent)| (name.clone(), format!("{:.0}%", percent)))
+      ),
+      Self::table(
+        tr(Key::DayLoad),
+        self
+          .stats
+          .days
+          .iter()
+          .map(|(day, load)| (map_weekday_to_str(*day).to_string(), format!("{:.1}", load)))
+      ),
+    ]
+    .spacing(20);
+
+    let content = column![header, scrollable(tables)].spacing(10).padding([10, 15]);
+    container(content).width(Length::Fill).into()
+  }
+}
@@ -0,0 +1,144 @@
+use chrono::Weekday;
+use iced::{
+  widget::{column, container, pick_list, row, scrollable, text, Rule},
+  Element, Length,
+};
+use iced_aw::Icon;
+use maiq_shared::default::DefaultLesson;
+
+use crate::{
+  diff,
+  i18n::{tr, trf, Key},
+  occupancy::overlaps,
+  substitution::Candidate,
+};
+
+use super::{basic_button, toolbar::map_weekday_to_str, with_icon, Component};
+
+// One lesson of the absent teacher and who could take it
+#[derive(Debug, Clone)]
+pub struct Substitution {
+  pub group: usize,
+  pub lesson: usize,
+  pub name: String,
+  pub original: DefaultLesson,
+  pub candidates: Vec<Candidate>,
+  pub chosen: Option<Candidate>,
+}
+
+#[derive(Debug)]
+pub struct SubstitutionView {
+  day: Weekday,
+  substitutions: Vec<Substitution>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+  Choose(usize, Candidate),
+  ApplyToEditor,
+  ToChanges,
+}
+
+impl Default for SubstitutionView {
+  fn default() -> Self {
+    Self { day: Weekday::Mon, substitutions: vec![] }
+  }
+}
+
+impl SubstitutionView {
+  pub fn set(&mut self, day: Weekday, substitutions: Vec<Substitution>) {
+    self.day = day;
+    self.substitutions = substitutions;
+  }
+
+  pub fn clear(&mut self) {
+    self.substitutions.clear();
+  }
+
+  pub fn day(&self) -> Weekday {
+    self.day
+  }
+
+  // Only the lessons someone was picked for
+  pub fn chosen(&self) -> impl Iterator<Item = (&Substitution, &str)> {
+    self
+      .substitutions
+      .iter()
+      .filter_map(|s| Some((s, s.chosen.as_ref()?.teacher.as_str())))
+  }
+
+  // A teacher picked for one lesson can't take another one at the same time
+  fn options(&self, idx: usize) -> Vec<Candidate> {
+    let current = &self.substitutions[idx];
+    let taken = self
+      .substitutions
+      .iter()
+      .enumerate()
+      .filter(|(other, s)| *other != idx && s.original.num == current.original.num)
+      .filter(|(_, s)| overlaps(s.original.is_even, current.original.is_even))
+      .filter_map(|(_, s)| Some(s.chosen.as_ref()?.teacher.as_str()))
+      .collect::<Vec<_>>();
+    current
+      .candidates
+      .iter()
+      .filter(|c| !taken.contains(&c.teacher.as_str()))
+      .cloned()
+      .collect()
+  }
+}
+
+impl Component for SubstitutionView {
+  type Message = Message;
+
+  fn update(&mut self, message: Self::Message) {
+    match message {
+      Message::Choose(idx, candidate) => {
+        if idx < self.substitutions.len() && self.options(idx).contains(&candidate) {
+          self.substitutions[idx].chosen = Some(candidate)
+        }
+      }
+      Message::ApplyToEditor | Message::ToChanges => (),
+    }
+  }
+
+  fn view(&self) -> Element<Self::Message> {
+    if self.substitutions.is_empty() {
+      return container(text(tr(Key::NothingSelected))).padding([10, 15]).into();
+    }
+
+    let header = row![
+      text(trf(Key::SubstitutionsFor, &[&map_weekday_to_str(self.day)])).width(Length::Fill),
+      basic_button(with_icon(tr(Key::ApplyToEditor), Icon::PencilSquare), Message::ApplyToEditor),
+      basic_button(with_icon(tr(Key::ToChanges), Icon::CalendarEvent), Message::ToChanges),
+    ]
+    .align_items(iced::Alignment::Center)
+    .spacing(10);
+
+    let rows = column(
+      self
+        .substitutions
+        .iter()
+        .enumerate()
+        .map(|(idx, s)| {
+          let options = self.options(idx);
+          let choice: Element<_> = match options.is_empty() {
+            true => text(tr(Key::NoCandidates)).into(),
+            false => pick_list(options, s.chosen.clone(), move |c| Message::Choose(idx, c))
+              .placeholder(tr(Key::Substitute))
+              .into(),
+          };
+          row![text(&s.name).width(Length::Fixed(100.)), text(diff::describe(&s.original)).width(Length::Fill), choice]
+            .align_items(iced::Alignment::Center)
+            .spacing(10)
+            .into()
+        })
+        .collect(),
+    )
+    .spacing(5);
+
+    let content = column![header, Rule::horizontal(1), scrollable(rows)]
+      .spacing(10)
+      .padding([10, 15]);
+    container(content).width(Length::Fill).into()
+  }
+}
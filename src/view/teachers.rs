@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use chrono::Weekday;
+use iced::{
+  theme::Button,
+  widget::{button, column, container, row, scrollable, text, text_input, Rule},
+  Element, Length,
+};
+use iced_aw::Icon;
+use maiq_shared::default::DefaultDay;
+
+use crate::{
+  env::WEEKDAYS,
+  i18n::{tr, Key},
+  ordering::natural_cmp,
+  pivot::{self, ExportFormat, TeacherLesson},
+};
+
+use super::{basic_button, toolbar::map_weekday_to_str, with_icon, Component};
+
+#[derive(Debug, Default)]
+pub struct TeachersView {
+  teachers: BTreeMap<String, Vec<TeacherLesson>>,
+  query: String,
+  selected: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+  EditQuery(String),
+  Select(String),
+  Export(ExportFormat),
+}
+
+impl TeachersView {
+  pub fn refresh(&mut self, days: &[DefaultDay]) {
+    self.teachers = pivot::teachers(days);
+    if self.selected.as_ref().is_some_and(|t| !self.teachers.contains_key(t)) {
+      self.selected = None;
+    }
+  }
+
+  pub fn selected(&self) -> Option<(&str, &[TeacherLesson])> {
+    let teacher = self.selected.as_ref()?;
+    Some((teacher.as_str(), self.teachers.get(teacher)?.as_slice()))
+  }
+
+  fn filtered(&self) -> Vec<&String> {
+    let query = self.query.trim().to_lowercase();
+    let mut teachers = self
+      .teachers
+      .keys()
+      .filter(|t| t.to_lowercase().contains(&query))
+      .collect::<Vec<_>>();
+    teachers.sort_by(|a, b| natural_cmp(a, b));
+    teachers
+  }
+
+  fn day_view(day: Weekday, lessons: &[TeacherLesson]) -> Element<Message> {
+    let rows = lessons
+      .iter()
+      .filter(|l| l.day == day)
+      .map(|l| {
+        let num = match l.subgroup {
+          Some(subgroup) => format!("{}/{}", l.num, subgroup),
+          None => l.num.to_string(),
+        };
+        row![
+          text(num).width(Length::Fixed(40.)),
+          text(&l.group).width(Length::Fixed(100.)),
+          text(&l.subject).width(Length::Fill),
+          text(l.classroom.as_deref().unwrap_or_default()).width(Length::Fixed(80.)),
+          text(pivot::parity(l.is_even)).width(Length::Fixed(90.)),
+        ]
+        .spacing(10)
+        .into()
+      })
+      .collect::<Vec<_>>();
+
+    match rows.is_empty() {
+      true => column![].into(),
+      false => column![text(map_weekday_to_str(day)).size(20), column(rows).spacing(2)]
+        .spacing(5)
+        .into(),
+    }
+  }
+}
+
+impl Component for TeachersView {
+  type Message = Message;
+
+  fn update(&mut self, message: Self::Message) {
+    match message {
+      Message::EditQuery(query) => self.query = query,
+      Message::Select(teacher) => self.selected = Some(teacher),
+      Message::Export(_) => (),
+    }
+  }
+
+  fn view(&self) -> Element<Self::Message> {
+    let list = column(
+      self
+        .filtered()
+        .into_iter()
+        .map(|teacher| {
+          let style = match self.selected.as_ref() == Some(teacher) {
+            true => Button::Primary,
+            false => Button::Secondary,
+          };
+          button(text(teacher))
+            .on_press(Message::Select(teacher.clone()))
+            .padding(4)
+            .width(Length::Fill)
+            .style(style)
+            .into()
+        })
+        .collect(),
+    )
+    .spacing(3);
+    let sidebar = column![text_input(tr(Key::TeacherFilter), &self.query, Message::EditQuery), scrollable(list)]
+      .spacing(10)
+      .width(Length::Fixed(240.));
+
+    let week: Element<_> = match self.selected() {
+      Some((teacher, lessons)) => {
+        let mut header = row![text(teacher).size(24).width(Length::Fill)]
+          .align_items(iced::Alignment::Center)
+          .spacing(10);
+        for format in ExportFormat::ALL {
+          header = header.push(basic_button(with_icon(text(format.to_string()), Icon::Upload), Message::Export(format)));
+        }
+        let days = WEEKDAYS.iter().map(|day| Self::day_view(*day, lessons)).collect();
+        column![header, Rule::horizontal(1), scrollable(column(days).spacing(15))]
+          .spacing(10)
+          .into()
+      }
+      None => text(tr(Key::SelectTeacher)).into(),
+    };
+
+    let content = row![sidebar, container(week).width(Length::Fill)]
+      .spacing(20)
+      .padding([10, 15]);
+    container(content).width(Length::Fill).into()
+  }
+}
@@ -10,7 +10,12 @@ use iced_aw::{
 };
 use maiq_shared::default::DefaultDay;
 
-use crate::{app::AppMessage, env::DEFAULTS};
+use crate::{
+  app::{AppMessage, Screen},
+  env::WEEKDAYS,
+  i18n::{tr, Key},
+  ordering::SortOrder,
+};
 
 use super::with_icon;
 
@@ -21,13 +26,14 @@ fn menu_button<'a>(content: impl Into<Element<'a>>, message: Message) -> MenuTre
   MenuTree::new(super::basic_button(content, message).width(Length::Fill))
 }
 
-fn file_menu<'a>() -> MenuTree<'a, Message, iced::Renderer> {
+fn file_menu<'a>(defaults: &[DefaultDay]) -> MenuTree<'a, Message, iced::Renderer> {
   MenuTree::with_children(
-    with_icon("Файл", Icon::FileEarmark),
+    with_icon(tr(Key::File), Icon::FileEarmark),
     vec![
-      menu_button(with_icon("Новый", Icon::FileEarmark), AppMessage::New),
-      import_menu(&DEFAULTS),
-      menu_button(with_icon("Экспорт", Icon::Upload), AppMessage::Export),
+      menu_button(with_icon(tr(Key::New), Icon::FileEarmark), AppMessage::New),
+      import_menu(defaults),
+      menu_button(with_icon(tr(Key::ImportHtml), Icon::FileEarmarkCode), AppMessage::ImportHtml),
+      menu_button(with_icon(tr(Key::Export), Icon::Upload), AppMessage::Export),
     ],
   )
 }
@@ -35,14 +41,14 @@ fn file_menu<'a>() -> MenuTree<'a, Message, iced::Renderer> {
 fn import_menu<'a>(default: &[DefaultDay]) -> MenuTree<'a, Message, iced::Renderer> {
   let mut childs = vec![
     MenuTree::new(
-      text("Стандартное")
+      text(tr(Key::Standard))
         .vertical_alignment(Vertical::Center)
         .horizontal_alignment(Horizontal::Center)
         .height(Length::Fill)
         .width(Length::Fill),
     ),
-    menu_button(with_icon("Сегодня", Icon::Calendar), AppMessage::ImportToday),
-    menu_button(with_icon("Завтра", Icon::Calendar), AppMessage::ImportNext)
+    menu_button(with_icon(tr(Key::Today), Icon::Calendar), AppMessage::ImportToday),
+    menu_button(with_icon(tr(Key::Tomorrow), Icon::Calendar), AppMessage::ImportNext),
   ];
   default
     .iter()
@@ -52,7 +58,7 @@ fn import_menu<'a>(default: &[DefaultDay]) -> MenuTree<'a, Message, iced::Render
 
   MenuTree::with_children(
     super::basic_button(
-      row![with_icon("Импорт", Icon::Download), text(Icon::ChevronBarRight).font(ICON_FONT)],
+      row![with_icon(tr(Key::Import), Icon::Download), text(Icon::ChevronBarRight).font(ICON_FONT)],
       AppMessage::Nothing,
     )
     .width(Length::Fill),
@@ -62,33 +68,86 @@ fn import_menu<'a>(default: &[DefaultDay]) -> MenuTree<'a, Message, iced::Render
 
 fn edit_menu<'a>() -> MenuTree<'a, Message, iced::Renderer> {
   MenuTree::with_children(
-    with_icon("Редактировать", Icon::PencilSquare),
-    vec![menu_button(with_icon("Сортировка", Icon::ArrowRepeat), AppMessage::Sort)],
+    with_icon(tr(Key::Edit), Icon::PencilSquare),
+    vec![
+      menu_button(with_icon(tr(Key::Copy), Icon::Clipboard), AppMessage::Copy),
+      copy_to_menu(),
+      sort_menu(),
+      menu_button(with_icon(tr(Key::FindSlots), Icon::Search), AppMessage::FindSlots),
+      menu_button(with_icon(tr(Key::FindSubstitutes), Icon::PersonCheck), AppMessage::FindSubstitutes),
+    ],
+  )
+}
+
+fn copy_to_menu<'a>() -> MenuTree<'a, Message, iced::Renderer> {
+  MenuTree::with_children(
+    super::basic_button(
+      row![with_icon(tr(Key::CopyTo), Icon::Files), text(Icon::ChevronBarRight).font(ICON_FONT)],
+      AppMessage::Nothing,
+    )
+    .width(Length::Fill),
+    WEEKDAYS
+      .iter()
+      .map(|d| menu_button(with_icon(map_weekday_to_str(*d), Icon::Calendar), AppMessage::CopyToDay(*d)))
+      .collect(),
+  )
+}
+
+fn sort_menu<'a>() -> MenuTree<'a, Message, iced::Renderer> {
+  MenuTree::with_children(
+    super::basic_button(
+      row![with_icon(tr(Key::Sorting), Icon::ArrowRepeat), text(Icon::ChevronBarRight).font(ICON_FONT)],
+      AppMessage::Nothing,
+    )
+    .width(Length::Fill),
+    SortOrder::ALL
+      .iter()
+      .map(|order| menu_button(text(order.to_string()), AppMessage::Sort(*order)))
+      .collect(),
+  )
+}
+
+fn view_menu<'a>() -> MenuTree<'a, Message, iced::Renderer> {
+  MenuTree::with_children(
+    with_icon(tr(Key::View), Icon::Window),
+    vec![
+      menu_button(with_icon(tr(Key::Editor), Icon::PencilSquare), AppMessage::Open(Screen::Editor)),
+      menu_button(with_icon(tr(Key::References), Icon::Book), AppMessage::Open(Screen::References)),
+      menu_button(with_icon(tr(Key::Changes), Icon::CalendarEvent), AppMessage::Open(Screen::Changes)),
+      menu_button(with_icon(tr(Key::FinalView), Icon::Eye), AppMessage::Open(Screen::Final)),
+      menu_button(with_icon(tr(Key::Teachers), Icon::People), AppMessage::Open(Screen::Teachers)),
+      menu_button(with_icon(tr(Key::Occupancy), Icon::DoorOpen), AppMessage::Open(Screen::Rooms)),
+      menu_button(with_icon(tr(Key::Statistics), Icon::BarChart), AppMessage::Open(Screen::Stats)),
+      menu_button(with_icon(tr(Key::Gaps), Icon::HourglassSplit), AppMessage::Open(Screen::Gaps)),
+      menu_button(with_icon(tr(Key::Curriculum), Icon::JournalCheck), AppMessage::Open(Screen::Curriculum)),
+      menu_button(with_icon(tr(Key::History), Icon::ClockHistory), AppMessage::Open(Screen::History)),
+      menu_button(with_icon(tr(Key::Settings), Icon::Gear), AppMessage::Open(Screen::Settings)),
+    ],
   )
 }
 
 fn server_menu<'a>() -> MenuTree<'a, Message, iced::Renderer> {
   MenuTree::with_children(
-    with_icon("Сервер", Icon::Cloud),
+    with_icon(tr(Key::Server), Icon::Cloud),
     vec![
-      menu_button(with_icon("API токен", Icon::Shield), AppMessage::Dummy),
-      menu_button(with_icon("Опубликовать", Icon::CloudUpload), AppMessage::Dummy),
+      menu_button(with_icon(tr(Key::ApiToken), Icon::Shield), AppMessage::Dummy),
+      menu_button(with_icon(tr(Key::Publish), Icon::CloudUpload), AppMessage::Dummy),
     ],
   )
 }
 
-pub fn toolbar<'a>() -> Element<'a> {
-  row![MenuBar::new(vec![file_menu(), edit_menu(), server_menu()]).spacing(10.0)].into()
+pub fn toolbar<'a>(defaults: &[DefaultDay]) -> Element<'a> {
+  row![MenuBar::new(vec![file_menu(defaults), edit_menu(), view_menu(), server_menu()]).spacing(10.0)].into()
 }
 
-const fn map_weekday_to_str(d: Weekday) -> &'static str {
-  match d {
-    Weekday::Mon => "Понедельник",
-    Weekday::Tue => "Вторник",
-    Weekday::Wed => "Среда",
-    Weekday::Thu => "Четверг",
-    Weekday::Fri => "Пятница",
-    Weekday::Sat => "Суббота",
-    Weekday::Sun => "Воскресенье",
-  }
+pub fn map_weekday_to_str(d: Weekday) -> &'static str {
+  tr(match d {
+    Weekday::Mon => Key::Monday,
+    Weekday::Tue => Key::Tuesday,
+    Weekday::Wed => Key::Wednesday,
+    Weekday::Thu => Key::Thursday,
+    Weekday::Fri => Key::Friday,
+    Weekday::Sat => Key::Saturday,
+    Weekday::Sun => Key::Sunday,
+  })
 }